@@ -3,6 +3,7 @@ use globset::{GlobBuilder, GlobMatcher};
 
 use crate::build::generator::CodeGenerator;
 use crate::build::handler::HttpHandler;
+use crate::layer::ApiDeprecation;
 
 #[derive(Debug, Default, With)]
 pub struct Builder {
@@ -18,6 +19,50 @@ pub struct Builder {
     output_path: Option<String>,
     /// 输出模块名称
     output_name: String,
+    /// 客户端输出模块名称，设置后生成基于 reqwest 的客户端，需启用 `client` feature
+    client_output_name: Option<String>,
+    /// 客户端类型路径映射，例如 `("crate::dto", "user_dto")`
+    client_type_mappings: Vec<(String, String)>,
+    /// 路由分组，例如 `/v1`、`/v2`、`/admin`
+    groups: Vec<ApiGroup>,
+}
+
+/// 路由分组，每个分组生成一个同名模块，包含独立的 `ApiDoc`、`new_router`、`routes` 和 `service_info`
+///
+/// 处理函数通过 `#[api_group(name)]` 属性或所在目录归入分组，未归入分组的处理函数仍生成在顶层。
+#[derive(Debug, Clone, Default, With)]
+pub struct ApiGroup {
+    /// 分组名称，同时作为生成的模块名
+    name: String,
+    /// 挂载路径，例如 `/v1`
+    prefix: String,
+    /// 相对于处理函数目录的子目录，目录下的处理函数归入该分组
+    dir: Option<String>,
+    /// 已弃用时为响应添加弃用响应头，并在文档中标记为已弃用
+    deprecation: Option<ApiDeprecation>,
+}
+
+impl ApiGroup {
+    pub fn new(name: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Self { name: name.into(), prefix: prefix.into(), ..Default::default() }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub(crate) fn deprecation(&self) -> Option<&ApiDeprecation> {
+        self.deprecation.as_ref()
+    }
+
+    /// 去除末尾 `/` 后的挂载路径，用于比较前缀是否冲突
+    fn mount_path(&self) -> &str {
+        self.prefix.trim_end_matches('/')
+    }
 }
 
 impl Builder {
@@ -31,14 +76,163 @@ impl Builder {
         self
     }
 
+    /// 添加客户端类型路径映射，将处理函数中以 `from` 开头的类型路径替换为 `to`
+    pub fn with_client_type_mapping(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.client_type_mappings.push((from.into(), to.into()));
+        self
+    }
+
+    /// 添加路由分组
+    pub fn with_group(mut self, group: ApiGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
     pub fn build(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let handlers = HttpHandler::parse(&self.project_path, &self.handlers_dir, &self.file_matcher)?;
-        let code = CodeGenerator::new(handlers, self.app_state.clone()).generate_code();
+        let mut handlers = HttpHandler::parse(&self.project_path, &self.handlers_dir, &self.file_matcher)?;
+        self.assign_groups(&mut handlers)?;
+        HttpHandler::check_duplicates(&handlers)?;
+        let mut generator = CodeGenerator::new(handlers, self.app_state.clone()).with_groups(self.groups.clone());
+        let code = generator.generate_code();
 
         let out_dir = self.output_path.clone().unwrap_or_else(|| std::env::var("OUT_DIR").unwrap());
         std::fs::write(format!("{}/{}.rs", out_dir, self.output_name), code)?;
 
+        if let Some(client_output_name) = &self.client_output_name {
+            let code = generator.generate_client(&self.client_type_mappings);
+            std::fs::write(format!("{}/{}.rs", out_dir, client_output_name), code)?;
+        }
+
         println!("cargo:rerun-if-changed={}", self.handlers_dir);
         Ok(())
     }
+
+    /// 校验分组配置，并将未声明分组的处理函数按目录归入分组
+    fn assign_groups(&self, handlers: &mut [HttpHandler]) -> Result<(), Box<dyn std::error::Error>> {
+        for (i, group) in self.groups.iter().enumerate() {
+            if syn::parse_str::<syn::Ident>(&group.name).is_err() {
+                return Err(format!("invalid api group name `{}`", group.name).into());
+            }
+            if self.groups[..i].iter().any(|g| g.name == group.name) {
+                return Err(format!("duplicate api group `{}`", group.name).into());
+            }
+            if self.groups[..i].iter().any(|g| g.mount_path() == group.mount_path()) {
+                return Err(format!("api group `{}` uses duplicate prefix `{}`", group.name, group.prefix).into());
+            }
+        }
+
+        for handler in handlers.iter_mut() {
+            match &handler.group {
+                Some(name) if !self.groups.iter().any(|g| &g.name == name) => {
+                    return Err(
+                        format!("handler `{}` uses unknown api group `{}`", handler.handler_path(), name).into()
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    let group = self
+                        .groups
+                        .iter()
+                        .find(|g| g.dir.as_ref().is_some_and(|dir| handler.file.starts_with(dir)));
+                    handler.group = group.map(|g| g.name.clone());
+                }
+            }
+        }
+
+        // 空前缀的分组与顶层处理函数挂载在同一路径下
+        if let Some(group) = self.groups.iter().find(|g| g.mount_path().is_empty())
+            && handlers.iter().any(|h| h.group.is_none())
+        {
+            return Err(
+                format!("api group `{}` with empty prefix conflicts with top-level handlers", group.name).into()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let handlers_dir = dir.path().join("src/handlers");
+        std::fs::create_dir_all(handlers_dir.join("v1")).unwrap();
+        std::fs::write(
+            handlers_dir.join("v1/user.rs"),
+            r#"
+#[utoipa::path(get, path = "/users")]
+pub async fn list_users() {}
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            handlers_dir.join("user.rs"),
+            r#"
+#[utoipa::path(get, path = "/users")]
+#[api_group(v2)]
+pub async fn list_users() {}
+
+#[utoipa::path(get, path = "/health")]
+pub async fn health() {}
+"#,
+        )
+        .unwrap();
+
+        let builder = Builder::default()
+            .with_project_path(dir.path().to_string_lossy())
+            .with_handlers_dir(handlers_dir.to_string_lossy())
+            .with_app_state("AppState")
+            .with_output_path(dir.path().to_string_lossy())
+            .with_output_name("api")
+            .with_client_output_name("client")
+            .with_group(
+                ApiGroup::new("v1", "/v1")
+                    .with_dir("v1")
+                    .with_deprecation(ApiDeprecation::new(1735689600, None, Some("/v2/users")).unwrap()),
+            )
+            .with_group(ApiGroup::new("v2", "/v2"));
+        builder.build().unwrap();
+
+        let code = std::fs::read_to_string(dir.path().join("api.rs")).unwrap();
+        let code: String = code.split_whitespace().collect();
+        assert!(code.contains(".route(\"/health\",get(crate::handlers::user::health))"));
+        assert!(code.contains("pubmodv1{"));
+        assert!(code.contains("pubconstPREFIX:&str=\"/v1\";"));
+        assert!(code.contains(".route(\"/users\",get(crate::handlers::v1::user::list_users))"));
+        assert!(code.contains("baizekit_api::layer::ApiDeprecation::new(1735689600i64,None,Some(\"/v2/users\"))"));
+        assert!(code.contains(".route(\"/users\",get(crate::handlers::user::list_users))"));
+        assert!(code.contains("vec![(axum::http::Method::GET,\"/users\")]"));
+        assert!(code.contains(".with_routes(routes())"));
+        assert!(code.contains("vec![v1::service_info(state.clone()),v2::service_info(state.clone())]"));
+
+        let client = std::fs::read_to_string(dir.path().join("client.rs")).unwrap();
+        assert!(client.contains("pub async fn v1_list_users("));
+        assert!(client.contains("\"/v2/users\""));
+
+        let builder = builder.with_group(ApiGroup::new("v1", "/legacy"));
+        assert!(builder.build().unwrap_err().to_string().contains("duplicate api group `v1`"));
+
+        let base = || {
+            Builder::default()
+                .with_project_path(dir.path().to_string_lossy())
+                .with_handlers_dir(handlers_dir.to_string_lossy())
+                .with_app_state("AppState")
+                .with_output_path(dir.path().to_string_lossy())
+                .with_output_name("api")
+        };
+        let builder = base()
+            .with_group(ApiGroup::new("v1", "/v1").with_dir("v1"))
+            .with_group(ApiGroup::new("v2", "/v1/"));
+        let err = builder.build().unwrap_err().to_string();
+        assert!(err.contains("api group `v2` uses duplicate prefix `/v1/`"));
+
+        let builder = base()
+            .with_group(ApiGroup::new("v1", "").with_dir("v1"))
+            .with_group(ApiGroup::new("v2", "/v2"));
+        let err = builder.build().unwrap_err().to_string();
+        assert!(err.contains("api group `v1` with empty prefix conflicts with top-level handlers"));
+    }
 }
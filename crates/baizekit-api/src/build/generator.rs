@@ -1,85 +1,495 @@
+use std::collections::HashMap;
+
 use axum::http::Method;
-use quote::{format_ident, quote};
-use syn::parse_file;
+use quote::{ToTokens, format_ident, quote};
+use syn::visit_mut::VisitMut;
+use syn::{TypePath, parse_file};
 
-use crate::build::handler::HttpHandler;
+use crate::build::builder::ApiGroup;
+use crate::build::handler::{HandlerInput, HandlerOutput, HttpHandler};
+use crate::layer::ApiDeprecation;
 
 pub(crate) struct CodeGenerator {
     handlers: Vec<HttpHandler>,
     state: String,
+    groups: Vec<ApiGroup>,
 }
 
 impl CodeGenerator {
     pub(crate) fn new(handlers: Vec<HttpHandler>, state: String) -> Self {
-        Self { handlers, state }
+        Self { handlers, state, groups: vec![] }
+    }
+
+    pub(crate) fn with_groups(mut self, groups: Vec<ApiGroup>) -> Self {
+        self.groups = groups;
+        self
     }
 
     pub(crate) fn generate_code(&mut self) -> String {
-        // 生成 Router
-        let mut router_chain = quote! { axum::Router::new() };
-        self.handlers.sort_by(|a, b| {
-            let lhs = (&a.http_path, a.http_method.to_string());
-            let rhs = (&b.http_path, b.http_method.to_string());
-            lhs.cmp(&rhs)
-        });
-        for handler in self.handlers.iter() {
-            let path = &handler.http_path;
-
-            let module: syn::Path = syn::parse_str(&handler.module).unwrap();
-            let func = format_ident!("{}", handler.func);
-
-            let method_token = match handler.http_method {
-                Method::GET => quote! { get },
-                Method::POST => quote! { post },
-                Method::PUT => quote! { put },
-                Method::DELETE => quote! { delete },
-                _ => continue, // 不支持的 method 跳过
-            };
+        let state = syn::parse_str::<syn::Path>(&self.state.clone()).unwrap();
+        let state = quote! { #state };
+
+        // 未归入分组的处理函数生成在顶层
+        let handlers = self.handlers.iter().filter(|h| h.group.is_none()).collect();
+        let api = generate_api(handlers, &state, None);
 
-            router_chain = quote! {
-                #router_chain
-                    .route(#path, #method_token(#module::#func))
+        let mut groups = vec![];
+        let mut service_infos = vec![];
+        for group in &self.groups {
+            let name = format_ident!("{}", group.name());
+            let prefix = group.prefix();
+            let handlers = self
+                .handlers
+                .iter()
+                .filter(|h| h.group.as_deref() == Some(group.name()))
+                .collect();
+            let api = generate_api(handlers, &state, group.deprecation());
+
+            let openapi = match group.deprecation() {
+                Some(_) => quote! {
+                    let mut openapi = <ApiDoc as utoipa::OpenApi>::openapi();
+                    deprecation().apply_openapi(&mut openapi);
+                    openapi
+                },
+                None => quote! { <ApiDoc as utoipa::OpenApi>::openapi() },
             };
+
+            groups.push(quote! {
+                pub mod #name {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    pub const PREFIX: &str = #prefix;
+
+                    #api
+
+                    pub fn openapi() -> utoipa::openapi::OpenApi {
+                        #openapi
+                    }
+
+                    pub fn service_info(state: #state) -> baizekit_api::component::axum::AxumServiceInfo {
+                        baizekit_api::component::axum::AxumServiceInfo::new(PREFIX, new_router(state), openapi())
+                            .with_routes(routes())
+                    }
+                }
+            });
+            service_infos.push(quote! { #name::service_info(state.clone()) });
         }
 
-        // 构建 ApiDoc paths
-        self.handlers.sort_by(|a, b| {
-            let lhs = (&a.module, &a.func);
-            let rhs = (&b.module, &b.func);
-            lhs.cmp(&rhs)
-        });
-        let paths: Vec<_> = self
-            .handlers
-            .iter()
-            .map(|h| {
-                let module = syn::parse_str::<syn::Path>(&h.module).unwrap();
-                let func = format_ident!("{}", h.func);
-                quote! { #module::#func }
-            })
-            .collect();
+        let service_infos = match self.groups.is_empty() {
+            true => quote! {},
+            false => quote! {
+                /// 所有分组的服务，可直接传给 `AxumComponentBuilder::add_services`
+                pub fn service_infos(state: #state) -> Vec<baizekit_api::component::axum::AxumServiceInfo> {
+                    vec![#(#service_infos),*]
+                }
+            },
+        };
 
-        let state = syn::parse_str::<syn::Path>(&self.state.clone()).unwrap();
-        let state = quote! { #state };
+        let output = quote! {
+            #api
+
+            #(#groups)*
+
+            #service_infos
+        };
+
+        pretty(output.to_string())
+    }
+
+    /// 生成基于 reqwest 的客户端，每个处理函数对应一个异步方法
+    ///
+    /// `type_mappings` 将处理函数所在 crate 中的类型路径映射到客户端 crate 可见的路径，
+    /// 例如 `("crate::dto", "user_dto")`。
+    pub(crate) fn generate_client(&self, type_mappings: &[(String, String)]) -> String {
+        let mut handlers: Vec<_> = self.handlers.iter().collect();
+        handlers.sort_by(|a, b| (&a.module, &a.func).cmp(&(&b.module, &b.func)));
+
+        let mut func_count: HashMap<&str, usize> = HashMap::new();
+        for handler in &handlers {
+            *func_count.entry(handler.func.as_str()).or_default() += 1;
+        }
+
+        let mut mapper = TypeMapper { mappings: type_mappings };
+        let mut methods = Vec::new();
+        for handler in handlers {
+            // 同名函数以分组名或模块名作为前缀
+            let name = match (func_count[handler.func.as_str()], &handler.group) {
+                (1, _) => format_ident!("{}", handler.func),
+                (_, Some(group)) => format_ident!("{}_{}", group, handler.func),
+                (_, None) => {
+                    let module = handler.module.rsplit("::").next().unwrap_or_default();
+                    format_ident!("{}_{}", module, handler.func)
+                }
+            };
+            let method = format_ident!("{}", handler.http_method.as_str());
+            let prefix = self
+                .groups
+                .iter()
+                .find(|g| handler.group.as_deref() == Some(g.name()))
+                .map(ApiGroup::prefix);
+            let path_template = &format!("{}{}", prefix.unwrap_or_default(), handler.http_path);
+
+            let mut params = Vec::new();
+            let mut path = quote! { let path = #path_template.to_string(); };
+            let mut queries = Vec::new();
+            let mut body = quote! { None::<&()> };
+            for input in &handler.inputs {
+                match input {
+                    HandlerInput::Path(ty) => {
+                        let ty = mapper.map(ty);
+                        params.push(quote! { path: &#ty });
+                        path = quote! { let path = baizekit_api::client::render_path(#path_template, path)?; };
+                    }
+                    HandlerInput::Query(ty) => {
+                        let name = match (ty.as_str(), queries.len()) {
+                            ("baizekit_api::extract::PageParams", _) => format_ident!("page"),
+                            (_, 0) => format_ident!("query"),
+                            (_, n) => format_ident!("query_{}", n + 1),
+                        };
+                        let ty = mapper.map(ty);
+                        params.push(quote! { #name: &#ty });
+                        queries.push(name);
+                    }
+                    HandlerInput::Json(ty) => {
+                        let ty = mapper.map(ty);
+                        params.push(quote! { body: &#ty });
+                        body = quote! { Some(body) };
+                    }
+                }
+            }
+
+            let doc = format!(" `{} {}`", handler.http_method, handler.http_path);
+            let (output, call) = match &handler.output {
+                Some(HandlerOutput::Reply(ty)) => (mapper.map(ty), quote! { call_reply }),
+                Some(HandlerOutput::Json(ty)) => (mapper.map(ty), quote! { call_json }),
+                None => (syn::parse_quote! { baizekit_api::client::reqwest::Response }, quote! { send }),
+            };
+
+            // 多个查询参数合并为键值对
+            let (query_pairs, query) = match queries.is_empty() {
+                true => (quote! {}, quote! { None::<&()> }),
+                false => (
+                    quote! {
+                        let mut query = Vec::new();
+                        #(query.extend(baizekit_api::client::query_pairs(#queries)?);)*
+                    },
+                    quote! { Some(&query) },
+                ),
+            };
+
+            methods.push(quote! {
+                #[doc = #doc]
+                pub async fn #name(&self, #(#params),*) -> Result<#output, baizekit_api::client::ClientError> {
+                    #path
+                    #query_pairs
+                    self.inner
+                        .#call(baizekit_api::client::reqwest::Method::#method, &path, #query, #body)
+                        .await
+                }
+            });
+        }
 
         let output = quote! {
-            #[derive(utoipa::OpenApi)]
-            #[openapi(paths(
-                #(#paths),*
-            ))]
-            pub struct ApiDoc;
+            /// 由 `baizekit_api::build::Builder` 生成的客户端
+            #[derive(Debug, Clone)]
+            pub struct ApiClient {
+                inner: baizekit_api::client::ApiClient,
+            }
+
+            impl ApiClient {
+                /// `base_url` 为服务的挂载地址，例如 `http://user-service:8080/api/users`
+                pub fn new(base_url: impl Into<String>) -> Self {
+                    Self { inner: baizekit_api::client::ApiClient::new(base_url) }
+                }
+
+                pub fn with_client(base_url: impl Into<String>, http: baizekit_api::client::reqwest::Client) -> Self {
+                    Self { inner: baizekit_api::client::ApiClient::with_client(base_url, http) }
+                }
 
-            pub fn new_router(state: #state) -> axum::Router {
-                use axum::routing::*;
+                pub fn inner(&self) -> &baizekit_api::client::ApiClient {
+                    &self.inner
+                }
 
-                #router_chain
-                    .with_state(state)
+                #(#methods)*
             }
         };
 
-        let code = output.to_string();
-        match parse_file(&code) {
-            Ok(parsed) => prettyplease::unparse(&parsed),
-            Err(_) => code,
+        pretty(output.to_string())
+    }
+}
+
+/// 生成一组处理函数的 `ApiDoc` 和 `new_router`
+fn generate_api(
+    mut handlers: Vec<&HttpHandler>,
+    state: &proc_macro2::TokenStream,
+    deprecation: Option<&ApiDeprecation>,
+) -> proc_macro2::TokenStream {
+    // 生成 Router
+    let mut router_chain = quote! { axum::Router::new() };
+    let mut routes = vec![];
+    handlers.sort_by(|a, b| {
+        let lhs = (&a.http_path, a.http_method.to_string());
+        let rhs = (&b.http_path, b.http_method.to_string());
+        lhs.cmp(&rhs)
+    });
+    for handler in handlers.iter() {
+        let path = &handler.http_path;
+
+        let func: syn::Path = syn::parse_str(&handler.handler_path()).unwrap();
+
+        let method_token = match handler.http_method {
+            Method::GET => quote! { get },
+            Method::POST => quote! { post },
+            Method::PUT => quote! { put },
+            Method::DELETE => quote! { delete },
+            Method::PATCH => quote! { patch },
+            Method::HEAD => quote! { head },
+            Method::OPTIONS => quote! { options },
+            Method::TRACE => quote! { trace },
+            _ => {
+                println!(
+                    "cargo:warning=skip handler `{}`: unsupported method {}",
+                    func.to_token_stream(),
+                    handler.http_method
+                );
+                continue;
+            }
+        };
+
+        // 先声明的中间件在最外层
+        let mut method_router = quote! { #method_token(#func) };
+        for middleware in handler.middlewares.iter().rev() {
+            let middleware: syn::Path = syn::parse_str(middleware).unwrap();
+            method_router = quote! {
+                #method_router.layer(axum::middleware::from_fn_with_state(state.clone(), #middleware))
+            };
+        }
+
+        router_chain = quote! {
+            #router_chain
+                .route(#path, #method_router)
+        };
+
+        let method = format_ident!("{}", handler.http_method.as_str());
+        routes.push(quote! { (axum::http::Method::#method, #path) });
+    }
+
+    // 构建 ApiDoc paths
+    handlers.sort_by(|a, b| {
+        let lhs = (&a.module, &a.func);
+        let rhs = (&b.module, &b.func);
+        lhs.cmp(&rhs)
+    });
+    let paths: Vec<_> = handlers
+        .iter()
+        .map(|h| syn::parse_str::<syn::Path>(&h.handler_path()).unwrap())
+        .collect();
+
+    let (deprecation_fn, deprecation_layer) = match deprecation {
+        Some(deprecation) => {
+            let since = deprecation.since();
+            let sunset = match deprecation.sunset() {
+                Some(sunset) => quote! { Some(#sunset) },
+                None => quote! { None },
+            };
+            let successor = match deprecation.successor() {
+                Some(successor) => quote! { Some(#successor) },
+                None => quote! { None },
+            };
+            (
+                quote! {
+                    pub fn deprecation() -> baizekit_api::layer::ApiDeprecation {
+                        baizekit_api::layer::ApiDeprecation::new(#since, #sunset, #successor)
+                            .expect("invalid api deprecation")
+                    }
+                },
+                quote! {
+                    .layer(axum::middleware::from_fn_with_state(deprecation(), baizekit_api::layer::api_deprecation))
+                },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    };
+
+    quote! {
+        #[derive(utoipa::OpenApi)]
+        #[openapi(paths(
+            #(#paths),*
+        ))]
+        pub struct ApiDoc;
+
+        #deprecation_fn
+
+        pub fn new_router(state: #state) -> axum::Router {
+            use axum::routing::*;
+
+            #router_chain
+                #deprecation_layer
+                .with_state(state)
         }
+
+        /// `new_router` 中的路由，用于构建路由表
+        pub fn routes() -> Vec<(axum::http::Method, &'static str)> {
+            vec![#(#routes),*]
+        }
+    }
+}
+
+fn pretty(code: String) -> String {
+    match parse_file(&code) {
+        Ok(parsed) => prettyplease::unparse(&parsed),
+        Err(_) => code,
+    }
+}
+
+/// 按前缀替换类型路径
+struct TypeMapper<'a> {
+    mappings: &'a [(String, String)],
+}
+
+impl TypeMapper<'_> {
+    fn map(&mut self, ty: &str) -> syn::Type {
+        let mut ty: syn::Type = syn::parse_str(ty).expect("handler type is parsed from source");
+        self.visit_type_mut(&mut ty);
+
+        let code = ty.to_token_stream().to_string().replace(' ', "");
+        if code.contains("crate::") {
+            println!("cargo:warning=client type `{}` is not mapped, use `with_client_type_mapping`", code);
+        }
+        ty
+    }
+}
+
+impl VisitMut for TypeMapper<'_> {
+    fn visit_type_path_mut(&mut self, type_path: &mut TypePath) {
+        syn::visit_mut::visit_type_path_mut(self, type_path);
+
+        let segments: Vec<String> = type_path.path.segments.iter().map(|s| s.ident.to_string()).collect();
+        for (from, to) in self.mappings {
+            let from: Vec<&str> = from.split("::").collect();
+            if segments.len() < from.len() || segments.iter().zip(&from).any(|(a, b)| a != b) {
+                continue;
+            }
+
+            let rest: Vec<_> = type_path.path.segments.iter().skip(from.len()).cloned().collect();
+            let mut mapped: syn::Path = syn::parse_str(to).expect("invalid client type mapping");
+            mapped.segments.extend(rest);
+            type_path.path = mapped;
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_client() {
+        let handlers = parse_handlers(
+            r#"
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use crate::dto::{CreateUser, UserDto};
+
+#[derive(serde::Deserialize)]
+pub struct UserFilter { pub name: String }
+
+#[utoipa::path(get, path = "/{id}")]
+pub async fn get_user(State(state): State<AppState>, Path(id): Path<i64>) -> ApiResult<UserDto, ApiErr> {}
+
+#[utoipa::path(get, path = "/")]
+pub async fn list_users(Query(filter): Query<UserFilter>, page: PageQuery) -> ApiResult<Page<UserDto>, ApiErr> {}
+
+#[utoipa::path(post, path = "/")]
+pub async fn create_user(Json(body): Json<CreateUser>) -> Result<Json<UserDto>, ApiError<ApiErr>> {}
+"#,
+        );
+        let generator = CodeGenerator::new(handlers, "AppState".to_string());
+        let code = generator.generate_client(&[("crate::dto".to_string(), "user_dto".to_string())]);
+        let code: String = code.split_whitespace().collect();
+
+        assert!(code.contains("pubasyncfnget_user(&self,path:&i64,)"));
+        assert!(code.contains("Result<user_dto::UserDto,baizekit_api::client::ClientError>"));
+        assert!(code.contains("baizekit_api::client::render_path(\"/{id}\",path)?"));
+        assert!(code.contains("query:&crate::handlers::user::UserFilter"));
+        assert!(code.contains("page:&baizekit_api::extract::PageParams"));
+        assert!(code.contains("query.extend(baizekit_api::client::query_pairs(page)?)"));
+        assert!(code.contains("Result<Page<user_dto::UserDto>,baizekit_api::client::ClientError>"));
+        assert!(code.contains("pubasyncfncreate_user(&self,body:&user_dto::CreateUser,)"));
+        assert!(code.contains(".call_json("));
+    }
+
+    fn parse_handlers(source: &str) -> Vec<HttpHandler> {
+        let dir = tempfile::tempdir().unwrap();
+        let handlers_dir = dir.path().join("src/handlers");
+        std::fs::create_dir_all(&handlers_dir).unwrap();
+        std::fs::write(handlers_dir.join("user.rs"), source).unwrap();
+        std::fs::write(handlers_dir.join("broken.rs"), "pub fn broken(").unwrap();
+
+        let project_path = dir.path().to_string_lossy().to_string();
+        HttpHandler::parse(&project_path, &handlers_dir.to_string_lossy().to_string(), &None).unwrap()
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let handlers = parse_handlers(
+            r#"
+use crate::middleware::auth;
+
+#[utoipa::path(patch, path = "/users/{id}")]
+#[baizekit::derive::middleware(auth, audit)]
+pub async fn patch_user() {}
+
+#[utoipa::path(head, path = "/users")]
+pub async fn head_users() {}
+
+async fn audit() {}
+
+pub mod admin {
+    #[utoipa::path(options, path = "/admin")]
+    pub async fn options_admin() {}
+}
+
+pub struct UserApi;
+
+impl UserApi {
+    #[utoipa::path(get, path = "/me")]
+    pub async fn me() {}
+}
+"#,
+        );
+        assert_eq!(handlers.len(), 3);
+        assert!(HttpHandler::check_duplicates(&handlers).is_ok());
+
+        let mut generator = CodeGenerator::new(handlers, "AppState".to_string());
+        let code: String = generator.generate_code().split_whitespace().collect();
+
+        assert!(code.contains(
+            ".route(\"/users/{id}\",patch(crate::handlers::user::patch_user).layer(axum::middleware::from_fn_with_state(state.clone(),crate::handlers::user::audit,),).layer(axum::middleware::from_fn_with_state(state.clone(),crate::middleware::auth,),),)"
+        ));
+        assert!(code.contains(".route(\"/users\",head(crate::handlers::user::head_users))"));
+        assert!(code.contains(".route(\"/admin\",options(crate::handlers::user::admin::options_admin))"));
+        assert!(code.contains("paths(crate::handlers::user::head_users,crate::handlers::user::patch_user,crate::handlers::user::admin::options_admin"));
+        assert!(!code.contains("UserApi"));
+    }
+
+    #[test]
+    fn test_duplicate_routes() {
+        let handlers = parse_handlers(
+            r#"
+#[utoipa::path(get, path = "/users")]
+pub async fn list_users() {}
+
+pub mod v2 {
+    #[utoipa::path(get, path = "/users")]
+    pub async fn list_users() {}
+}
+"#,
+        );
+        let err = HttpHandler::check_duplicates(&handlers).unwrap_err();
+        assert!(err.contains("`GET /users`"));
+        assert!(err.contains("crate::handlers::user::v2::list_users"));
     }
 }
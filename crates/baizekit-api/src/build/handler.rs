@@ -1,9 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::path::PathBuf;
 
 use axum::http::Method;
 use globset::GlobMatcher;
+use quote::{ToTokens, format_ident};
 use syn::punctuated::Punctuated;
-use syn::{parse_file, Attribute, Expr, Item, Lit, Meta, Token};
+use syn::visit_mut::VisitMut;
+use syn::{
+    Attribute, Expr, FnArg, GenericArgument, ImplItem, Item, Lit, Meta, PathArguments, PathSegment, ReturnType,
+    Signature, Token, Type, TypePath, UseTree, parse_file,
+};
 use walkdir::WalkDir;
 
 #[derive(Clone, Debug)]
@@ -16,6 +23,36 @@ pub(crate) struct HttpHandler {
     pub http_method: Method,
     /// HTTP 路径
     pub http_path: String,
+    /// `#[middleware(...)]` 声明的中间件函数，已解析为完整路径
+    pub middlewares: Vec<String>,
+    /// `#[api_group(...)]` 声明的分组，可声明在处理函数或内联的 `mod` 上
+    pub group: Option<String>,
+    /// 所在文件相对于处理函数目录的路径
+    pub file: PathBuf,
+    /// 请求参数，类型已解析为完整路径
+    pub inputs: Vec<HandlerInput>,
+    /// 响应数据，无法识别时为空
+    pub output: Option<HandlerOutput>,
+}
+
+/// 处理函数的请求参数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HandlerInput {
+    /// `Path<T>`
+    Path(String),
+    /// `Query<T>`，`PageQuery` 按 `PageParams` 处理
+    Query(String),
+    /// `Json<T>`
+    Json(String),
+}
+
+/// 处理函数的响应数据
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HandlerOutput {
+    /// `ApiOK<T>`、`ApiResult<T, E>` 等包装在 `Reply` 中的数据
+    Reply(String),
+    /// `Json<T>`
+    Json(String),
 }
 
 impl HttpHandler {
@@ -35,28 +72,148 @@ impl HttpHandler {
 
         let mut handlers = vec![];
         for entry in entries {
-            let Some(module) = file_path_to_mod_path(entry.path(), &cargo_src_path) else { continue };
-            let Ok(content) = fs::read_to_string(entry.path()) else { continue };
-            let Ok(syntax) = parse_file(&content) else { continue };
-
-            for item in syntax.items {
-                if let Item::Fn(func) = item {
-                    for attr in &func.attrs {
-                        if let Some((method, path)) = parse_utoipa_path(attr) {
-                            handlers.push(HttpHandler {
-                                module: module.clone(),
-                                func: func.sig.ident.to_string(),
-                                http_method: method,
-                                http_path: path,
-                            });
-                        }
-                    }
+            let path = entry.path().display();
+            let Some(module) = file_path_to_mod_path(entry.path(), &cargo_src_path) else {
+                println!("cargo:warning=skip {}: not under {}", path, cargo_src_path.display());
+                continue;
+            };
+            let content = match fs::read_to_string(entry.path()) {
+                Ok(content) => content,
+                Err(err) => {
+                    println!("cargo:warning=skip {}: {}", path, err);
+                    continue;
                 }
-            }
+            };
+            let syntax = match parse_file(&content) {
+                Ok(syntax) => syntax,
+                Err(err) => {
+                    println!("cargo:warning=skip {}: {}", path, err);
+                    continue;
+                }
+            };
+            let start = handlers.len();
+            parse_items(&module, None, &syntax.items, &mut handlers);
+            let file = entry.path().strip_prefix(http_handlers_dir).unwrap_or(entry.path());
+            handlers[start..]
+                .iter_mut()
+                .for_each(|handler| handler.file = file.to_path_buf());
         }
 
         Ok(handlers)
     }
+
+    /// 处理函数的完整路径，例如 `crate::handlers::user::get_user`
+    pub(crate) fn handler_path(&self) -> String {
+        format!("{}::{}", self.module, self.func)
+    }
+
+    /// 检查是否存在相同方法和路径的路由，axum 会在运行时因此 panic
+    pub(crate) fn check_duplicates(handlers: &[HttpHandler]) -> Result<(), String> {
+        let mut routes: HashMap<(Option<&str>, &str, &Method), &HttpHandler> = HashMap::new();
+        let mut errors = vec![];
+        for handler in handlers {
+            let key = (handler.group.as_deref(), handler.http_path.as_str(), &handler.http_method);
+            if let Some(exists) = routes.insert(key, handler) {
+                errors.push(format!(
+                    "duplicate route `{} {}`: `{}` and `{}`",
+                    handler.http_method,
+                    handler.http_path,
+                    exists.handler_path(),
+                    handler.handler_path()
+                ));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors.join("\n")),
+        }
+    }
+}
+
+/// 解析一个模块中的处理函数，包括内联的 `mod`
+fn parse_items(module: &str, group: Option<&String>, items: &[Item], handlers: &mut Vec<HttpHandler>) {
+    let resolver = TypeResolver::new(module, items);
+
+    for item in items {
+        match item {
+            Item::Fn(func) => {
+                handlers.extend(parse_handler(module, group, &func.attrs, &func.sig, &resolver));
+            }
+            // `#[utoipa::path]` 会在原位置展开出 `impl utoipa::Path for ...`，即使设置 `impl_for`
+            // 也无法放在 `impl` 块中编译，提示移动到模块中
+            Item::Impl(item_impl) => {
+                let funcs = item_impl.items.iter().filter_map(|item| match item {
+                    ImplItem::Fn(func) if func.attrs.iter().any(is_utoipa_path) => Some(&func.sig.ident),
+                    _ => None,
+                });
+                for func in funcs {
+                    println!(
+                        "cargo:warning=skip handler `{}::{}`: `#[utoipa::path]` expands to trait impls which cannot be placed in impl blocks (even with `impl_for`), move it to module scope",
+                        module, func
+                    );
+                }
+            }
+            Item::Mod(item_mod) => {
+                if let Some((_, items)) = &item_mod.content {
+                    let group = parse_group(&item_mod.attrs).or(group.cloned());
+                    parse_items(&format!("{}::{}", module, item_mod.ident), group.as_ref(), items, handlers);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_handler(
+    module: &str,
+    group: Option<&String>,
+    attrs: &[Attribute],
+    sig: &Signature,
+    resolver: &TypeResolver,
+) -> Option<HttpHandler> {
+    let (http_method, http_path) = attrs.iter().find_map(parse_utoipa_path)?;
+
+    let resolve_path = |path: &syn::Path| resolver.resolve(&Type::Path(TypePath { qself: None, path: path.clone() }));
+    let middlewares = attrs
+        .iter()
+        .filter(|attr| attr.path().segments.last().is_some_and(|s| s.ident == "middleware"))
+        .filter_map(|attr| match attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated) {
+            Ok(paths) => Some(paths),
+            Err(err) => {
+                println!("cargo:warning=invalid middleware attribute on `{}::{}`: {}", module, sig.ident, err);
+                None
+            }
+        })
+        .flatten()
+        .map(|path| resolve_path(&path).replace(' ', ""))
+        .collect();
+
+    Some(HttpHandler {
+        module: module.to_string(),
+        func: sig.ident.to_string(),
+        http_method,
+        http_path,
+        middlewares,
+        group: parse_group(attrs).or(group.cloned()),
+        file: PathBuf::new(),
+        inputs: parse_inputs(sig, resolver),
+        output: parse_output(sig, resolver),
+    })
+}
+
+/// 解析 `#[api_group(v1)]` 属性
+fn parse_group(attrs: &[Attribute]) -> Option<String> {
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().segments.last().is_some_and(|s| s.ident == "api_group"))?;
+    match attr.parse_args::<syn::Ident>() {
+        Ok(ident) => Some(ident.to_string()),
+        Err(err) => {
+            println!("cargo:warning=invalid api_group attribute: {}", err);
+            None
+        }
+    }
 }
 
 /// 判断是否有 Utoipa 的 path 属性
@@ -89,6 +246,9 @@ fn parse_utoipa_path(attr: &Attribute) -> Option<(Method, String)> {
                     "put" => Some(Method::PUT),
                     "delete" => Some(Method::DELETE),
                     "patch" => Some(Method::PATCH),
+                    "head" => Some(Method::HEAD),
+                    "options" => Some(Method::OPTIONS),
+                    "trace" => Some(Method::TRACE),
                     _ => method,
                 };
             }
@@ -112,6 +272,192 @@ fn parse_utoipa_path(attr: &Attribute) -> Option<(Method, String)> {
     method.zip(path)
 }
 
+/// 解析处理函数参数中的 `Path`、`Query`、`Json` 提取器
+fn parse_inputs(sig: &Signature, resolver: &TypeResolver) -> Vec<HandlerInput> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(&*pat_type.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .filter_map(|ty| {
+            let segment = last_segment(ty)?;
+            let input = match segment.ident.to_string().as_str() {
+                "Path" => HandlerInput::Path(resolver.resolve(first_generic(segment)?)),
+                "Query" => HandlerInput::Query(resolver.resolve(first_generic(segment)?)),
+                "Json" => HandlerInput::Json(resolver.resolve(first_generic(segment)?)),
+                "PageQuery" => HandlerInput::Query("baizekit_api::extract::PageParams".to_string()),
+                _ => return None,
+            };
+            Some(input)
+        })
+        .collect()
+}
+
+/// 解析处理函数返回值中的数据类型
+fn parse_output(sig: &Signature, resolver: &TypeResolver) -> Option<HandlerOutput> {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return None;
+    };
+
+    let mut ty = &**ty;
+    loop {
+        let segment = last_segment(ty)?;
+        match segment.ident.to_string().as_str() {
+            "ApiResult" | "ApiOK" => return Some(HandlerOutput::Reply(resolver.resolve(first_generic(segment)?))),
+            "Json" => return Some(HandlerOutput::Json(resolver.resolve(first_generic(segment)?))),
+            "Result" => ty = first_generic(segment)?,
+            _ => return None,
+        }
+    }
+}
+
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn first_generic(segment: &PathSegment) -> Option<&Type> {
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// 按文件中的 `use` 语句和本地定义，将类型解析为完整路径，例如 `UserDto` -> `crate::dto::UserDto`
+struct TypeResolver {
+    module: Vec<String>,
+    uses: HashMap<String, Vec<String>>,
+    locals: HashSet<String>,
+}
+
+impl TypeResolver {
+    fn new(module: &str, items: &[Item]) -> Self {
+        let module: Vec<String> = module.split("::").map(str::to_string).collect();
+        let mut uses = HashMap::new();
+        let mut locals = HashSet::new();
+
+        for item in items {
+            match item {
+                Item::Use(item_use) => collect_uses(&item_use.tree, Vec::new(), &mut uses),
+                Item::Struct(item) => {
+                    locals.insert(item.ident.to_string());
+                }
+                Item::Enum(item) => {
+                    locals.insert(item.ident.to_string());
+                }
+                Item::Type(item) => {
+                    locals.insert(item.ident.to_string());
+                }
+                // 中间件函数
+                Item::Fn(item) => {
+                    locals.insert(item.sig.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+
+        let mut resolver = Self { module, uses: HashMap::new(), locals };
+        resolver.uses = uses.into_iter().map(|(name, path)| (name, resolver.absolute(&path))).collect();
+        resolver
+    }
+
+    fn resolve(&self, ty: &Type) -> String {
+        let mut ty = ty.clone();
+        ResolveVisitor(self).visit_type_mut(&mut ty);
+        ty.to_token_stream().to_string()
+    }
+
+    /// 将 `self::`/`super::` 开头的路径转换为 `crate::` 开头的路径
+    fn absolute(&self, path: &[String]) -> Vec<String> {
+        let mut base = self.module.clone();
+        let mut rest = path;
+        match rest.first().map(String::as_str) {
+            Some("self") => rest = &rest[1..],
+            Some("super") => {
+                while rest.first().map(String::as_str) == Some("super") {
+                    base.pop();
+                    rest = &rest[1..];
+                }
+            }
+            _ => return path.to_vec(),
+        }
+        base.extend(rest.iter().cloned());
+        base
+    }
+}
+
+struct ResolveVisitor<'a>(&'a TypeResolver);
+
+impl VisitMut for ResolveVisitor<'_> {
+    fn visit_type_path_mut(&mut self, type_path: &mut TypePath) {
+        syn::visit_mut::visit_type_path_mut(self, type_path);
+        if type_path.qself.is_some() || type_path.path.leading_colon.is_some() {
+            return;
+        }
+
+        let resolver = self.0;
+        let segments: Vec<String> = type_path.path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let (head, skip) = match segments[0].as_str() {
+            "self" | "super" => {
+                let count = segments.iter().take_while(|s| *s == "self" || *s == "super").count();
+                (resolver.absolute(&segments[..count]), count)
+            }
+            first => match resolver.uses.get(first) {
+                Some(path) => (path.clone(), 1),
+                None if segments.len() == 1 && resolver.locals.contains(first) => {
+                    let mut path = resolver.module.clone();
+                    path.push(first.to_string());
+                    (path, 1)
+                }
+                None => return,
+            },
+        };
+
+        let original: Vec<PathSegment> = type_path.path.segments.iter().cloned().collect();
+        let mut resolved: Punctuated<PathSegment, Token![::]> =
+            head.iter().map(|ident| PathSegment::from(format_ident!("{}", ident))).collect();
+        if let (Some(last), Some(replaced)) = (resolved.last_mut(), original.get(skip - 1)) {
+            last.arguments = replaced.arguments.clone();
+        }
+        resolved.extend(original.into_iter().skip(skip));
+        type_path.path.segments = resolved;
+    }
+}
+
+fn collect_uses(tree: &UseTree, mut prefix: Vec<String>, uses: &mut HashMap<String, Vec<String>>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            collect_uses(&path.tree, prefix, uses);
+        }
+        UseTree::Name(name) if name.ident == "self" => {
+            if let Some(last) = prefix.last().cloned() {
+                uses.insert(last, prefix);
+            }
+        }
+        UseTree::Name(name) => {
+            prefix.push(name.ident.to_string());
+            uses.insert(name.ident.to_string(), prefix);
+        }
+        UseTree::Rename(rename) => {
+            prefix.push(rename.ident.to_string());
+            uses.insert(rename.rename.to_string(), prefix);
+        }
+        UseTree::Glob(_) => {}
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                collect_uses(tree, prefix.clone(), uses);
+            }
+        }
+    }
+}
+
 /// 将文件路径（例如 ./src/foo/bar.rs）转换为对应的 Rust 模块路径，例如 foo::bar
 /// - `crate_src_root`: 项目中 src 目录的路径，例如 /project_root/src
 /// - `file_path`: 要转换的 .rs 文件路径
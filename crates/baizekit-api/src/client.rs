@@ -0,0 +1,285 @@
+use std::fmt::{Display, Formatter};
+
+pub use reqwest;
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::response::Reply;
+
+/// 客户端调用错误
+#[derive(Debug)]
+pub enum ClientError {
+    /// 请求发送或响应读取失败
+    Http(reqwest::Error),
+    /// 路径参数或响应解析失败
+    Codec(String),
+    /// 服务端返回的业务错误
+    Api { status: StatusCode, code: i32, message: String },
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Http(err) => write!(f, "http error: {}", err),
+            ClientError::Codec(err) => write!(f, "codec error: {}", err),
+            ClientError::Api { status, code, message } => write!(f, "api error [{} {}]: {}", status, code, message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(err: reqwest::Error) -> Self {
+        ClientError::Http(err)
+    }
+}
+
+/// 生成的客户端的底层实现，由 `build::Builder::with_client_output_name` 生成的代码调用
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    /// 使用自定义的 `reqwest::Client`，例如配置超时、默认请求头或 TLS
+    pub fn with_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self { base_url, http }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 创建请求构建器，可在生成的方法之外调用未生成的接口
+    pub fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.http.request(method, format!("{}{}", self.base_url, path))
+    }
+
+    /// 发送请求并解析 [`Reply`] 中的 `data`；`code` 非 0 时返回 [`ClientError::Api`]
+    pub async fn call_reply<Q, B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let resp = self.send(method, path, query, body).await?;
+        let status = resp.status();
+        let bytes = resp.bytes().await?;
+
+        let reply: Reply<Value> = serde_json::from_slice(&bytes).map_err(|err| match status.is_success() {
+            true => ClientError::Codec(err.to_string()),
+            false => ClientError::Api { status, code: status.as_u16() as i32, message: body_text(&bytes) },
+        })?;
+        if reply.code != 0 || !status.is_success() {
+            return Err(ClientError::Api { status, code: reply.code, message: reply.message });
+        }
+
+        serde_json::from_value(reply.data.unwrap_or(Value::Null)).map_err(|err| ClientError::Codec(err.to_string()))
+    }
+
+    /// 发送请求并直接解析 JSON 响应体，用于返回 `Json<T>` 的接口
+    pub async fn call_json<Q, B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<T, ClientError>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let resp = self.send(method, path, query, body).await?;
+        let status = resp.status();
+        let bytes = resp.bytes().await?;
+        if !status.is_success() {
+            let (code, message) = match serde_json::from_slice::<Reply<Value>>(&bytes) {
+                Ok(reply) => (reply.code, reply.message),
+                Err(_) => (status.as_u16() as i32, body_text(&bytes)),
+            };
+            return Err(ClientError::Api { status, code, message });
+        }
+
+        serde_json::from_slice(&bytes).map_err(|err| ClientError::Codec(err.to_string()))
+    }
+
+    /// 发送请求，不解析响应
+    pub async fn send<Q, B>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<reqwest::Response, ClientError>
+    where
+        Q: Serialize + ?Sized,
+        B: Serialize + ?Sized,
+    {
+        let mut req = self.request(method, path);
+        if let Some(query) = query {
+            req = req.query(query);
+        }
+        if let Some(body) = body {
+            req = req.json(body);
+        }
+        Ok(req.send().await?)
+    }
+}
+
+fn body_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// 按路由模板填充路径参数，例如 `/users/{id}`
+///
+/// 参数为结构体时按字段名填充，为元组时按顺序填充，为单个值时填充第一个参数。
+pub fn render_path<T: Serialize + ?Sized>(template: &str, params: &T) -> Result<String, ClientError> {
+    let value = serde_json::to_value(params).map_err(|err| ClientError::Codec(err.to_string()))?;
+
+    let mut path = String::with_capacity(template.len());
+    let mut rest = template;
+    let mut index = 0;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| ClientError::Codec(format!("invalid path template '{}'", template)))?;
+        let name = rest[start + 1..end].trim_start_matches('*');
+
+        let param = match &value {
+            Value::Object(map) => map.get(name),
+            Value::Array(items) => items.get(index),
+            scalar if index == 0 => Some(scalar),
+            _ => None,
+        };
+        let param = match param {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Bool(b)) => b.to_string(),
+            _ => return Err(ClientError::Codec(format!("missing path parameter '{}'", name))),
+        };
+
+        path.push_str(&rest[..start]);
+        path.push_str(&encode_segment(&param));
+        rest = &rest[end + 1..];
+        index += 1;
+    }
+    path.push_str(rest);
+    Ok(path)
+}
+
+/// 将查询参数转换为键值对，空值忽略，数组展开为同名的多个参数
+pub fn query_pairs<T: Serialize + ?Sized>(params: &T) -> Result<Vec<(String, String)>, ClientError> {
+    let value = serde_json::to_value(params).map_err(|err| ClientError::Codec(err.to_string()))?;
+    let Value::Object(map) = value else {
+        return Err(ClientError::Codec("query parameters must be a struct or map".to_string()));
+    };
+
+    let mut pairs = Vec::with_capacity(map.len());
+    for (key, value) in map {
+        let values = match value {
+            Value::Array(items) => items,
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                Value::Null => {}
+                Value::String(s) => pairs.push((key.clone(), s)),
+                value => pairs.push((key.clone(), value.to_string())),
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// 百分号编码路径段中的保留字符
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::extract::Path;
+    use axum::routing::get;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::response::ApiOK;
+
+    #[derive(Serialize)]
+    struct OrderPath {
+        user_id: i64,
+        order_id: String,
+    }
+
+    #[test]
+    fn test_render_path() {
+        assert_eq!(render_path("/users/{id}", &7).unwrap(), "/users/7");
+        assert_eq!(render_path("/users/{id}/orders/{no}", &(7, "a b")).unwrap(), "/users/7/orders/a%20b");
+
+        let params = OrderPath { user_id: 1, order_id: "x".to_string() };
+        assert_eq!(render_path("/u/{user_id}/o/{order_id}", &params).unwrap(), "/u/1/o/x");
+        assert!(render_path("/u/{missing}", &params).is_err());
+
+        let query = serde_json::json!({ "name": "a", "ids": [1, 2], "cursor": null });
+        let pairs = query_pairs(&query).unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs.contains(&("ids".to_string(), "2".to_string())));
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        id: i64,
+    }
+
+    #[tokio::test]
+    async fn test_call_reply() {
+        let router = Router::new()
+            .route(
+                "/users/{id}",
+                get(|Path(id): Path<i64>| async move { ApiOK::with_data(serde_json::json!({ "id": id })) }),
+            )
+            .route(
+                "/fail",
+                get(|| async { Reply::<()> { code: 10001, message: "not found".to_string(), data: None } }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let client = ApiClient::new(format!("http://{}/", addr));
+        let path = render_path("/users/{id}", &42).unwrap();
+        let user: User = client.call_reply::<(), (), _>(Method::GET, &path, None, None).await.unwrap();
+        assert_eq!(user, User { id: 42 });
+
+        let err = client
+            .call_reply::<(), (), ()>(Method::GET, "/fail", None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::Api { code: 10001, .. }));
+    }
+}
@@ -1,11 +1,15 @@
+use std::collections::{BTreeMap, HashMap};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::body::Body;
-use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
-use axum::http::{Method, Request};
-use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::handler::Handler;
+use axum::http::header::HeaderName;
+use axum::http::{HeaderValue, Method};
+use axum::routing::MethodFilter;
+use axum::{Extension, Router};
 use baizekit_app::anyhow::Context;
 use baizekit_app::anyhow::Result;
 use baizekit_app::application::ApplicationInner;
@@ -14,27 +18,164 @@ use baizekit_app::component::Component;
 use baizekit_app::config::Config;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
-use tokio::net::TcpListener;
+use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
+use tower_http::compression::predicate::{NotForContentType, Predicate};
+use tower_http::compression::{CompressionLayer, DefaultPredicate};
 pub use tower_http::cors::AllowOrigin;
-use tower_http::cors::CorsLayer;
-use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer};
-use tower_http::trace;
-use tower_http::trace::TraceLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::set_header::SetResponseHeaderLayer;
+use tracing::info;
 pub use tracing::Level;
-use tracing::{info, Span};
-use utoipa::openapi::path::Operation;
+use utoipa::openapi::path::{Operation, PathItem};
+use utoipa::openapi::tag::TagBuilder;
 use utoipa::openapi::{Info, OpenApi, Paths};
-use utoipa_swagger_ui::SwaggerUi;
 
-#[derive(Debug, Deserialize)]
+pub use crate::component::docs::{DocsBasicAuth, DocsConfig, DocsSecurityScheme, DocsServer, DocsUi};
+pub use crate::component::routes::{RouteInfo, RouteRegistry, RoutesConfig};
+use crate::component::server::Listener;
+pub use crate::component::static_files::{EmbeddedAssets, StaticAsset, StaticDir, StaticFilesConfig, StaticSource};
+use crate::component::{server, static_files};
+use crate::extract::{PageQueryConfig, PrincipalSigner, PrincipalSigningConfig};
+use crate::layer::{
+    Audit, AuditConfig, AuditSink, CacheConfig, CatchPanic, CatchPanicConfig, CorsConfig, Csrf, CsrfConfig,
+    Idempotency, IdempotencyConfig, IdempotencyStore, MemoryIdempotencyStore, MemoryRateLimitStore,
+    MemoryResponseCacheStore, RateLimitConfig, RateLimitStore, RateLimiter, ReplyConfig, ReplyHook, ReplyRenderer,
+    ResponseCache, ResponseCacheStore, SecurityHeaders, SecurityHeadersConfig, TracingAuditSink, audit, catch_panic,
+    csrf, idempotency, rate_limit, reply_negotiation, request_timeout, response_cache, security_headers, trace_layer,
+};
+use crate::response::NDJSON_CONTENT_TYPE;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct AxumComponentConfig {
     /// 服务器监听地址,格式：IP: 0.0.0.0:8080 或 `[::1]:8080`
     #[serde(deserialize_with = "deserialize_socket_addr")]
     pub addr: SocketAddr,
+    /// 分页参数默认值与上限，见 [`crate::extract::PageQuery`]
+    pub page: PageQueryConfig,
+    /// 身份请求头签名，配置密钥后 `AdminPrincipal`/`EndUserPrincipal` 只接受签名有效的请求头
+    pub principal_signing: PrincipalSigningConfig,
+    /// 限流规则，需配合 [`AxumComponentBuilder::with_rate_limit`] 启用
+    pub rate_limit: RateLimitConfig,
+    /// 幂等键配置，需配合 [`AxumComponentBuilder::with_idempotency`] 启用
+    pub idempotency: IdempotencyConfig,
+    /// GET 响应缓存配置，需配合 [`AxumComponentBuilder::with_response_cache`] 启用
+    pub cache: CacheConfig,
+    /// 审计日志配置，需配合 [`AxumComponentBuilder::with_audit`] 启用
+    pub audit: AuditConfig,
+    /// 请求体大小上限(字节)，未配置时使用 axum 默认的 2MB
+    pub body_limit: Option<usize>,
+    /// 请求超时时间(秒)，超时返回 `Reply` 格式的 504
+    pub request_timeout_seconds: Option<u64>,
+    /// 处理函数 panic 时返回 `Reply` 格式的 500
+    pub catch_panic: CatchPanicConfig,
+    /// CORS 配置，需配合 [`AxumComponentBuilder::with_cors`] 启用
+    pub cors: CorsConfig,
+    /// 安全响应头配置，需配合 [`AxumComponentBuilder::with_security_headers`] 启用
+    pub security_headers: SecurityHeadersConfig,
+    /// CSRF 防护配置，需配合 [`AxumComponentBuilder::with_csrf`] 启用
+    pub csrf: CsrfConfig,
+    /// 优雅关闭等待时间(秒)，超时后强制关闭剩余连接
+    pub shutdown_timeout_seconds: u64,
+    /// 是否启用 HTTP/2，关闭时仅支持 HTTP/1.1
+    pub http2: bool,
+    /// HTTP/2 单个连接的并发流上限，未配置时使用 hyper 的默认值
+    pub http2_max_concurrent_streams: Option<u32>,
+    /// 是否启用 TCP_NODELAY
+    pub tcp_nodelay: bool,
+    /// TCP keepalive 空闲时间(秒)，未配置时使用系统默认值
+    pub tcp_keepalive_seconds: Option<u64>,
+    /// 响应压缩算法，为空时不压缩，例如 `["gzip", "br"]`
+    pub compression: Vec<CompressionAlgorithm>,
+    /// 默认响应头，仅在响应中不存在该头时添加
+    pub response_headers: HashMap<String, String>,
+    /// TLS 配置，需启用 `tls` feature
+    pub tls: Option<TlsConfig>,
+    /// 接口文档配置
+    pub docs: DocsConfig,
+    /// 路由表端点配置
+    pub routes: RoutesConfig,
+    /// 静态资源配置，需配合 [`AxumComponentBuilder::with_static_dir`] 等方法启用
+    pub static_files: StaticFilesConfig,
+    /// 指标端点配置，需配合 [`AxumComponentBuilder::with_metrics`] 启用
+    pub metrics: MetricsConfig,
+    /// 响应包装与附加字段配置
+    pub reply: ReplyConfig,
+    /// WebSocket 配置，需配合 [`AxumComponentBuilder::with_ws_hub`] 启用
+    #[cfg(feature = "ws")]
+    pub ws: crate::ws::WsConfig,
+    /// 文件上传限制，需配合 [`AxumComponentBuilder::with_upload_storage`] 使用
+    #[cfg(feature = "upload")]
+    pub upload: crate::upload::UploadConfig,
+    /// 额外的监听器，键为监听器名称；`default` 监听器默认由 `addr` 和 `tls` 生成，也可在此覆盖
+    pub listeners: HashMap<String, ListenerConfig>,
+}
+
+/// 指标端点配置，读取自 `axum.{label}.metrics`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// 指标端点路径
+    pub path: String,
+    /// 暴露指标端点的监听器，为空时所有监听器都暴露，例如 `["internal"]`
+    pub listeners: Vec<String>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { path: "/metrics".to_string(), listeners: Vec::new() }
+    }
+}
+
+/// 默认监听器名称
+pub const DEFAULT_LISTENER: &str = "default";
+
+/// 监听器配置，读取自 `axum.{label}.listeners.{name}`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ListenerConfig {
+    /// TCP 监听地址，与 `path` 二选一
+    #[serde(deserialize_with = "deserialize_opt_socket_addr")]
+    pub addr: Option<SocketAddr>,
+    /// Unix socket 路径，与 `addr` 二选一
+    pub path: Option<String>,
+    /// Unix socket 文件权限(八进制)，例如 `"660"`
+    pub mode: Option<String>,
+    /// 是否启用 TLS，证书取自 `axum.{label}.tls`，仅支持 TCP 监听器
+    pub tls: bool,
+}
+
+/// TLS 配置，读取自 `axum.{label}.tls`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsConfig {
+    /// 证书链 PEM 文件路径
+    pub cert_path: String,
+    /// 私钥 PEM 文件路径
+    pub key_path: String,
+    /// 客户端 CA 证书 PEM 文件路径，配置后启用 mTLS
+    pub client_ca_path: Option<String>,
+    /// 是否允许客户端不提供证书，仅在配置了 `client_ca_path` 时生效
+    #[serde(default)]
+    pub client_auth_optional: bool,
+    /// 证书文件变更检查间隔(秒)，为 0 时不热更新
+    #[serde(default = "default_tls_reload_interval")]
+    pub reload_interval_seconds: u64,
+}
+
+fn default_tls_reload_interval() -> u64 {
+    10
 }
 
-fn deserialize_socket_addr<'de, D>(deserializer: D) -> Result<SocketAddr, D::Error>
+/// 响应压缩算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Br,
+}
+
+pub(crate) fn deserialize_socket_addr<'de, D>(deserializer: D) -> Result<SocketAddr, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -44,30 +185,179 @@ where
         .map_err(|e| D::Error::custom(format!("invalid socket address '{}': {}", addr_str, e)))
 }
 
+fn deserialize_opt_socket_addr<'de, D>(deserializer: D) -> Result<Option<SocketAddr>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(addr_str) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    SocketAddr::from_str(&addr_str)
+        .map(Some)
+        .map_err(|e| D::Error::custom(format!("invalid socket address '{}': {}", addr_str, e)))
+}
+
+impl AxumComponentConfig {
+    /// 实际生效的监听器，包含由 `addr` 生成的 `default` 监听器
+    pub fn effective_listeners(&self) -> BTreeMap<String, ListenerConfig> {
+        let default = ListenerConfig { addr: Some(self.addr), tls: self.tls.is_some(), ..Default::default() };
+
+        let mut listeners = BTreeMap::from([(DEFAULT_LISTENER.to_string(), default)]);
+        listeners.extend(self.listeners.clone());
+        listeners
+    }
+}
+
 impl Default for AxumComponentConfig {
     fn default() -> Self {
-        Self { addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 8080) }
+        Self {
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 8080),
+            page: PageQueryConfig::default(),
+            principal_signing: PrincipalSigningConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            cache: CacheConfig::default(),
+            audit: AuditConfig::default(),
+            body_limit: None,
+            request_timeout_seconds: None,
+            catch_panic: CatchPanicConfig::default(),
+            cors: CorsConfig::default(),
+            security_headers: SecurityHeadersConfig::default(),
+            csrf: CsrfConfig::default(),
+            shutdown_timeout_seconds: 30,
+            http2: true,
+            http2_max_concurrent_streams: None,
+            tcp_nodelay: true,
+            tcp_keepalive_seconds: None,
+            compression: Vec::new(),
+            response_headers: HashMap::new(),
+            tls: None,
+            docs: DocsConfig::default(),
+            routes: RoutesConfig::default(),
+            static_files: StaticFilesConfig::default(),
+            metrics: MetricsConfig::default(),
+            reply: ReplyConfig::default(),
+            #[cfg(feature = "ws")]
+            ws: crate::ws::WsConfig::default(),
+            #[cfg(feature = "upload")]
+            upload: crate::upload::UploadConfig::default(),
+            listeners: HashMap::new(),
+        }
     }
 }
 
 pub struct AxumComponent {
-    router: Router,
-    openapi: OpenApi,
+    routers: Vec<ListenerRouter>,
+    routes: Arc<RouteRegistry>,
     config: AxumComponentConfig,
+    #[cfg(feature = "ws")]
+    ws_hub: Option<crate::ws::WsHub>,
     shutdown_trigger: CancellationToken,
     shutdown_done: CancellationToken,
 }
 
+struct ListenerRouter {
+    name: String,
+    conf: ListenerConfig,
+    router: Router,
+}
+
 pub struct AxumServiceInfo {
     pub path: String,
     pub router: Router,
     pub openapi: OpenApi,
+    /// 服务挂载的监听器，为空时挂载到 `default` 监听器
+    pub listeners: Vec<String>,
+    /// 服务的 OpenAPI 标签，未声明标签的接口会归入该标签
+    pub tag: Option<(String, Option<String>)>,
+    /// 服务的路由，通过 [`AxumServiceInfo::with_route`] 添加或 [`AxumServiceInfo::with_routes`] 声明，用于构建路由表
+    pub routes: Vec<(Method, String)>,
 }
 
 impl AxumServiceInfo {
     pub fn new(path: impl Into<String>, router: Router, openapi: OpenApi) -> Self {
-        AxumServiceInfo { path: path.into(), router, openapi }
+        AxumServiceInfo { path: path.into(), router, openapi, listeners: Vec::new(), tag: None, routes: Vec::new() }
     }
+
+    /// 添加路由并记录到路由表，适用于没有 `#[utoipa::path]` 的路由
+    pub fn with_route<H, T>(mut self, method: Method, path: impl Into<String>, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        let path = path.into();
+        let filter = MethodFilter::try_from(method.clone()).expect("unsupported http method");
+        self.router = self.router.route(&path, axum::routing::on(filter, handler));
+        self.routes.push((method, path));
+        self
+    }
+
+    /// 声明 `router` 中已有的路由，`router` 有路由但未声明时构建失败
+    pub fn with_routes<I, P>(mut self, routes: I) -> Self
+    where
+        I: IntoIterator<Item = (Method, P)>,
+        P: Into<String>,
+    {
+        self.routes
+            .extend(routes.into_iter().map(|(method, path)| (method, path.into())));
+        self
+    }
+
+    /// 设置服务的 OpenAPI 标签
+    pub fn with_tag(mut self, name: impl Into<String>, description: Option<String>) -> Self {
+        self.tag = Some((name.into(), description));
+        self
+    }
+
+    /// 将服务挂载到指定监听器，可多次调用挂载到多个监听器
+    pub fn on_listener(mut self, name: impl Into<String>) -> Self {
+        self.listeners.push(name.into());
+        self
+    }
+
+    fn serves(&self, listener: &str) -> bool {
+        match self.listeners.is_empty() {
+            true => listener == DEFAULT_LISTENER,
+            false => self.listeners.iter().any(|name| name == listener),
+        }
+    }
+
+    /// 带服务标签的 OpenAPI
+    fn tagged_openapi(&self) -> OpenApi {
+        let mut openapi = self.openapi.clone();
+        let Some((name, description)) = &self.tag else {
+            return openapi;
+        };
+
+        for path_item in openapi.paths.paths.values_mut() {
+            for operation in operations_mut(path_item) {
+                let tags = operation.tags.get_or_insert_with(Vec::new);
+                if tags.is_empty() {
+                    tags.push(name.clone());
+                }
+            }
+        }
+
+        let tag = TagBuilder::new().name(name).description(description.clone()).build();
+        openapi.tags.get_or_insert_with(Vec::new).push(tag);
+        openapi
+    }
+}
+
+fn operations_mut(path_item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut path_item.get,
+        &mut path_item.put,
+        &mut path_item.post,
+        &mut path_item.delete,
+        &mut path_item.options,
+        &mut path_item.head,
+        &mut path_item.patch,
+        &mut path_item.trace,
+    ]
+    .into_iter()
+    .filter_map(Option::as_mut)
 }
 
 pub struct AxumComponentBuilder {
@@ -76,6 +366,24 @@ pub struct AxumComponentBuilder {
     openapi_title: String,
     openapi_version: String,
     layers: Vec<Box<dyn Fn(Router) -> Router + Send + Sync + 'static>>,
+    rate_limit_store: Option<Arc<dyn RateLimitStore>>,
+    idempotency_store: Option<Arc<dyn IdempotencyStore>>,
+    response_cache_store: Option<Arc<dyn ResponseCacheStore>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    reply_hooks: Vec<Arc<dyn ReplyHook>>,
+    cors: bool,
+    cors_origin: Option<AllowOrigin>,
+    security_headers: bool,
+    csrf: bool,
+    static_mounts: Vec<(String, Arc<dyn StaticSource>)>,
+    #[cfg(feature = "metrics")]
+    metrics: bool,
+    #[cfg(feature = "ws")]
+    ws_hub: Option<crate::ws::WsHub>,
+    #[cfg(feature = "upload")]
+    upload_storage: Option<Arc<dyn crate::upload::UploadStorage>>,
+    #[cfg(feature = "grpc")]
+    grpc: Option<Router>,
 }
 
 impl AxumComponentBuilder {
@@ -86,6 +394,24 @@ impl AxumComponentBuilder {
             openapi_title: "App".to_string(),
             openapi_version: "0.1.0".to_string(),
             layers: Vec::new(),
+            rate_limit_store: None,
+            idempotency_store: None,
+            response_cache_store: None,
+            audit_sink: None,
+            reply_hooks: Vec::new(),
+            cors: false,
+            cors_origin: None,
+            security_headers: false,
+            csrf: false,
+            static_mounts: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: false,
+            #[cfg(feature = "ws")]
+            ws_hub: None,
+            #[cfg(feature = "upload")]
+            upload_storage: None,
+            #[cfg(feature = "grpc")]
+            grpc: None,
         }
     }
 
@@ -110,47 +436,123 @@ impl AxumComponentBuilder {
         self
     }
 
-    /// 配置CORS
-    /// allow_origin: 允许的源，None时默认使用Any
+    /// 配置CORS，允许的方法、请求头、凭据和缓存时间读取自 `axum.{label}.cors`
+    /// allow_origin: 允许的源，None时使用配置的 `allow_origins`，未配置时允许任意源
     pub fn with_cors(mut self, allow_origin: Option<AllowOrigin>) -> Self {
-        let allow_origin = allow_origin.unwrap_or(AllowOrigin::any());
+        self.cors = true;
+        self.cors_origin = allow_origin;
+        self
+    }
 
-        self.layers.push(Box::new(move |router| {
-            let cors_layer = CorsLayer::new()
-                .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE])
-                .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-                .allow_origin(allow_origin.clone());
+    /// 添加 HSTS、CSP 等安全响应头，配置读取自 `axum.{label}.security_headers`
+    pub fn with_security_headers(mut self) -> Self {
+        self.security_headers = true;
+        self
+    }
 
-            router.layer(cors_layer)
-        }));
+    /// 启用双重提交 Cookie 的 CSRF 防护，适用于使用 Cookie 认证的管理后台，配置读取自 `axum.{label}.csrf`
+    pub fn with_csrf(mut self) -> Self {
+        self.csrf = true;
         self
     }
 
-    /// 配置请求追踪
+    /// 配置请求追踪，启用 `otel` feature 时从 `traceparent` 请求头恢复上游链路
     /// level: 追踪级别，None时默认使用INFO
     pub fn with_trace(mut self, level: Option<Level>) -> Self {
         let trace_level = level.unwrap_or(Level::INFO);
 
         self.layers.push(Box::new(move |router| {
-            let mut router = router.layer(
-                TraceLayer::new_for_http()
-                    .make_span_with(|request: &Request<Body>| {
-                        let x_request_id = request.extensions().get::<RequestId>();
-                        tracing::debug_span!("request", ?x_request_id, method = %request.method(), uri = %request.uri())
-                    })
-                    .on_request(|r: &Request<Body>, _span: &Span| info!("request: {} {}", r.method(), r.uri().path()))
-                    .on_response(trace::DefaultOnResponse::new().level(trace_level)),
-            );
-
-            router = router
-                .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid::default()))
-                .layer(PropagateRequestIdLayer::x_request_id());
-
             router
+                .layer(trace_layer(trace_level))
+                .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid::default()))
+                .layer(PropagateRequestIdLayer::x_request_id())
         }));
         self
     }
 
+    /// 配置限流，规则读取自 `axum.{label}.rate_limit`
+    /// store: 令牌桶存储，None时默认使用进程内存储；多实例部署时使用 `RedisRateLimitStore`
+    pub fn with_rate_limit(mut self, store: Option<Arc<dyn RateLimitStore>>) -> Self {
+        self.rate_limit_store = Some(store.unwrap_or_else(|| Arc::new(MemoryRateLimitStore::new())));
+        self
+    }
+
+    /// 启用 `Idempotency-Key` 幂等处理，配置读取自 `axum.{label}.idempotency`
+    /// store: 响应存储，None时默认使用进程内存储；多实例部署时使用 `RedisIdempotencyStore`
+    pub fn with_idempotency(mut self, store: Option<Arc<dyn IdempotencyStore>>) -> Self {
+        self.idempotency_store = Some(store.unwrap_or_else(|| Arc::new(MemoryIdempotencyStore::new())));
+        self
+    }
+
+    /// 启用 GET 响应缓存，按路由前缀配置的规则读取自 `axum.{label}.cache`
+    /// store: 缓存存储，None时默认使用进程内 LRU 缓存；多实例部署时使用 `RedisResponseCacheStore`
+    /// 缓存位于限流和 CSRF 之内，命中缓存的请求同样计入限流
+    pub fn with_response_cache(mut self, store: Option<Arc<dyn ResponseCacheStore>>) -> Self {
+        let store = store
+            .unwrap_or_else(|| Arc::new(MemoryResponseCacheStore::new(MemoryResponseCacheStore::DEFAULT_CAPACITY)));
+        self.response_cache_store = Some(store);
+        self
+    }
+
+    /// 启用审计日志，配置读取自 `axum.{label}.audit`
+    /// sink: 审计记录输出，None时默认输出到 `audit` target 的 tracing 日志
+    pub fn with_audit(mut self, sink: Option<Arc<dyn AuditSink>>) -> Self {
+        self.audit_sink = Some(sink.unwrap_or_else(|| Arc::new(TracingAuditSink)));
+        self
+    }
+
+    /// 为每个 `Reply` 添加额外字段，内置的 `request_id`、`timestamp` 字段通过 `axum.{label}.reply` 启用
+    pub fn with_reply_hook(mut self, hook: Arc<dyn ReplyHook>) -> Self {
+        self.reply_hooks.push(hook);
+        self
+    }
+
+    /// 启用 HTTP 指标采集，并按 `axum.{label}.metrics` 暴露 Prometheus 指标端点
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = true;
+        self
+    }
+
+    /// 注册 WebSocket 连接注册表，配置读取自 `axum.{label}.ws`，组件关闭时关闭所有连接
+    #[cfg(feature = "ws")]
+    pub fn with_ws_hub(mut self, hub: crate::ws::WsHub) -> Self {
+        self.ws_hub = Some(hub);
+        self
+    }
+
+    /// 注册 [`Upload`](crate::upload::Upload) 提取器写入的存储，上传限制读取自 `axum.{label}.upload`
+    #[cfg(feature = "upload")]
+    pub fn with_upload_storage(mut self, storage: impl crate::upload::UploadStorage + 'static) -> Self {
+        self.upload_storage = Some(Arc::new(storage));
+        self
+    }
+
+    /// 将本地目录挂载到 `mount`，例如 `with_static_dir("web/dist", "/admin")`，配置读取自 `axum.{label}.static_files`
+    pub fn with_static_dir(self, path: impl Into<std::path::PathBuf>, mount: impl Into<String>) -> Self {
+        self.with_static_source(Arc::new(StaticDir::new(path)), mount)
+    }
+
+    /// 将编译期嵌入的资源挂载到 `mount`
+    pub fn with_embedded_assets(self, assets: EmbeddedAssets, mount: impl Into<String>) -> Self {
+        self.with_static_source(Arc::new(assets), mount)
+    }
+
+    /// 将自定义的资源来源挂载到 `mount`，挂载到 `/` 时作为未匹配路由的回退
+    pub fn with_static_source(mut self, source: Arc<dyn StaticSource>, mount: impl Into<String>) -> Self {
+        self.static_mounts.push((mount.into(), source));
+        self
+    }
+
+    /// 在 `default` 监听器上同时提供 gRPC 服务，路由取自 [`crate::component::grpc::GrpcComponent::router`]
+    ///
+    /// gRPC 请求不经过 HTTP 的中间件，需保持 `http2` 开启。
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(mut self, router: Router) -> Self {
+        self.grpc = Some(router);
+        self
+    }
+
     pub fn with_layer<F>(mut self, layer: F) -> Self
     where
         F: Fn(Router) -> Router + Send + Sync + 'static,
@@ -159,18 +561,100 @@ impl AxumComponentBuilder {
         self
     }
 
+    /// 构建路由表，包含各服务的文档化接口和声明的路由
+    ///
+    /// 服务的 `Router` 有路由但没有通过 [`AxumServiceInfo::with_route`] 或 [`AxumServiceInfo::with_routes`]
+    /// 声明任何路由时返回错误，避免路由表遗漏未文档化的路由。
+    pub fn route_registry(&self, conf: &AxumComponentConfig) -> Result<RouteRegistry> {
+        let mut registry = RouteRegistry::default();
+        for info in &self.services {
+            if info.router.has_routes() && info.routes.is_empty() {
+                baizekit_app::anyhow::bail!(
+                    "服务 {} 的路由未声明，请通过 with_route 添加或 with_routes 声明",
+                    info.path
+                );
+            }
+            let mut openapi = info.tagged_openapi();
+            conf.docs.apply_metadata(&mut openapi)?;
+            let listeners = match info.listeners.is_empty() {
+                true => vec![DEFAULT_LISTENER.to_string()],
+                false => info.listeners.clone(),
+            };
+            registry.add_service(&info.path, &openapi, &info.routes, &listeners);
+        }
+
+        if self.default_health_route {
+            registry.add_builtin("/health", &[]);
+        }
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            registry.add_builtin(&conf.metrics.path, &conf.metrics.listeners);
+        }
+        if conf.docs.enabled {
+            registry.add_builtin(&conf.docs.ui_path, &conf.docs.listeners);
+            registry.add_builtin(&conf.docs.openapi_path, &conf.docs.listeners);
+        }
+        if conf.routes.enabled {
+            registry.add_builtin(&conf.routes.path, &conf.routes.listeners);
+        }
+
+        Ok(registry)
+    }
+
     pub async fn build(self, inner: Arc<ApplicationInner>, label: String) -> Result<AxumComponent> {
         let config = inner.config().await;
         let conf: AxumComponentConfig = config.get(format!("axum.{}", label).as_str())?;
 
         let shutdown_token = CancellationToken::new();
+        let listeners = conf.effective_listeners();
 
+        let mut openapi = OpenApi::new(Info::new(&self.openapi_title, &self.openapi_version), Paths::new());
+        for info in &self.services {
+            if let Some(name) = info.listeners.iter().find(|name| !listeners.contains_key(*name)) {
+                baizekit_app::anyhow::bail!("[{}] 服务 {} 挂载到了未配置的监听器 '{}'", label, info.path, name);
+            }
+            openapi = openapi.nest(&info.path, info.tagged_openapi());
+        }
+        conf.docs.apply_metadata(&mut openapi)?;
+        conf.docs.dump(&openapi)?;
+
+        let routes = Arc::new(self.route_registry(&conf)?);
+
+        #[cfg(feature = "ws")]
+        if let Some(hub) = &self.ws_hub {
+            hub.configure(conf.ws.clone());
+        }
+
+        let mut routers = Vec::with_capacity(listeners.len());
+        for (name, listener) in listeners {
+            let router = self.build_router(&name, &conf, &routes)?;
+            routers.push(ListenerRouter { name, conf: listener, router });
+        }
+
+        Ok(AxumComponent {
+            routers,
+            routes,
+            config: conf,
+            #[cfg(feature = "ws")]
+            ws_hub: self.ws_hub,
+            shutdown_trigger: shutdown_token.child_token(),
+            shutdown_done: shutdown_token,
+        })
+    }
+
+    /// 构建指定监听器的路由，仅包含挂载到该监听器的服务
+    pub(crate) fn build_router(
+        &self,
+        listener: &str,
+        conf: &AxumComponentConfig,
+        routes: &Arc<RouteRegistry>,
+    ) -> Result<Router> {
         let mut router = Router::new();
         let mut openapi = OpenApi::new(Info::new(&self.openapi_title, &self.openapi_version), Paths::new());
 
-        for info in &self.services {
+        for info in self.services.iter().filter(|info| info.serves(listener)) {
             router = router.nest(&info.path, info.router.clone());
-            openapi = openapi.nest(&info.path, info.openapi.clone());
+            openapi = openapi.nest(&info.path, info.tagged_openapi());
         }
 
         let router = if self.default_health_route {
@@ -179,43 +663,211 @@ impl AxumComponentBuilder {
             router
         };
 
-        let mut router = router.merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi.clone()));
+        #[cfg(feature = "metrics")]
+        let router = match self.metrics {
+            true if conf.metrics.listeners.is_empty() || conf.metrics.listeners.iter().any(|l| l == listener) => {
+                router.route(&conf.metrics.path, axum::routing::get(crate::layer::metrics_handler))
+            }
+            _ => router,
+        };
+
+        let router = match conf.docs.serves(listener) {
+            true => {
+                conf.docs.apply_metadata(&mut openapi)?;
+                router.merge(conf.docs.router(openapi))
+            }
+            false => router,
+        };
+
+        let router = match conf.routes.serves(listener) {
+            true => router.merge(routes.clone().router(&conf.routes.path)),
+            false => router,
+        };
+
+        let mut router = router;
+        if conf.static_files.serves(listener) {
+            for (mount, source) in &self.static_mounts {
+                let service = static_files::router(source.clone(), &conf.static_files);
+                router = match mount.trim_end_matches('/') {
+                    "" => router.fallback_service(service),
+                    mount => router.nest_service(mount, service),
+                };
+            }
+        }
+
+        let signer = PrincipalSigner::new(&conf.principal_signing);
+        conf.page.validate()?;
+        let mut router = router.layer(Extension(conf.page));
+        if let Some(signer) = &signer {
+            router = router.layer(Extension(signer.clone()));
+        }
 
-        for layer in self.layers {
+        // 位于响应协商之内，504 响应同样按协商结果编码
+        if let Some(secs) = conf.request_timeout_seconds {
+            router = router.layer(axum::middleware::from_fn_with_state(Duration::from_secs(secs), request_timeout));
+        }
+
+        #[cfg(feature = "ws")]
+        if let Some(hub) = &self.ws_hub {
+            router = router.layer(Extension(hub.clone()));
+        }
+        #[cfg(feature = "upload")]
+        if let Some(storage) = &self.upload_storage {
+            router = router.layer(Extension(conf.upload.clone())).layer(Extension(storage.clone()));
+        }
+
+        let renderer = ReplyRenderer::new(&conf.reply, self.reply_hooks.clone());
+        router = router.layer(axum::middleware::from_fn_with_state(renderer, reply_negotiation));
+
+        // 位于响应协商之外，保存和重放的是最终编码后的响应
+        if let Some(store) = &self.idempotency_store {
+            let state = Idempotency::new(conf.idempotency.clone(), store.clone()).with_signer(signer.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(state, idempotency));
+        }
+        if let Some(store) = &self.response_cache_store {
+            let state = ResponseCache::new(conf.cache.clone(), store.clone())?.with_signer(signer.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(state, response_cache));
+        }
+
+        // 位于幂等和缓存之外，重放和命中缓存的请求同样受限流和 CSRF 校验
+        if let Some(store) = &self.rate_limit_store {
+            let limiter = RateLimiter::new(conf.rate_limit.clone(), store.clone())?.with_signer(signer.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(limiter, rate_limit));
+        }
+
+        if self.csrf {
+            let state = Csrf::new(conf.csrf.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(state, csrf));
+        }
+
+        // 位于限流和幂等之外，被拒绝和重放的请求同样记录
+        if let Some(sink) = &self.audit_sink {
+            let state = Audit::new(conf.audit.clone(), sink.clone()).with_signer(signer.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(state, audit));
+        }
+
+        // 位于业务中间件之外，响应钩子、审计、幂等和缓存中的 panic 同样返回 500
+        if conf.catch_panic.enabled {
+            let state = CatchPanic::new(conf.catch_panic.clone());
+            router = router.layer(axum::middleware::from_fn_with_state(state, catch_panic));
+        }
+
+        router = apply_server_layers(router, conf)?;
+
+        if self.security_headers {
+            let state = SecurityHeaders::new(&conf.security_headers)?;
+            router = router.layer(axum::middleware::from_fn_with_state(state, security_headers));
+        }
+
+        #[cfg(feature = "metrics")]
+        if self.metrics {
+            router = router.layer(axum::middleware::from_fn(crate::layer::http_metrics));
+        }
+
+        // 位于其他中间件之外，预检请求直接返回
+        if self.cors {
+            router = router.layer(conf.cors.layer(self.cors_origin.clone())?);
+        }
+
+        for layer in &self.layers {
             router = layer(router);
         }
 
-        Ok(AxumComponent {
-            router,
-            openapi,
-            config: conf,
-            shutdown_trigger: shutdown_token.child_token(),
-            shutdown_done: shutdown_token,
-        })
+        // 在所有 HTTP 中间件之后合并，gRPC 路由自带请求 ID 和追踪
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.grpc
+            && listener == DEFAULT_LISTENER
+        {
+            if !conf.http2 {
+                baizekit_app::anyhow::bail!("gRPC 与 HTTP 共用端口时需要开启 http2");
+            }
+            router = router.merge(grpc.clone());
+        }
+
+        Ok(router)
     }
 }
 
+/// 按配置添加请求体大小限制、响应压缩和默认响应头
+fn apply_server_layers(mut router: Router, conf: &AxumComponentConfig) -> Result<Router> {
+    if let Some(limit) = conf.body_limit {
+        router = router.layer(DefaultBodyLimit::max(limit));
+    }
+
+    for (name, value) in &conf.response_headers {
+        let name = HeaderName::from_str(name).with_context(|| format!("invalid response header name '{}'", name))?;
+        let value =
+            HeaderValue::from_str(value).with_context(|| format!("invalid response header value '{}'", value))?;
+        router = router.layer(SetResponseHeaderLayer::if_not_present(name, value));
+    }
+
+    if !conf.compression.is_empty() {
+        let compression = CompressionLayer::new()
+            .gzip(conf.compression.contains(&CompressionAlgorithm::Gzip))
+            .br(conf.compression.contains(&CompressionAlgorithm::Br))
+            // 压缩会缓冲 NDJSON 流式响应，与 SSE 一样跳过
+            .compress_when(DefaultPredicate::new().and(NotForContentType::const_new(NDJSON_CONTENT_TYPE)));
+        router = router.layer(compression);
+    }
+
+    Ok(router)
+}
+
 #[async_trait]
 impl Component for AxumComponent {
     async fn init(&mut self, _config: &Config, label: String) -> Result<()> {
-        let listener = TcpListener::bind(self.config.addr).await.context("listener bind failed.")?;
-        info!("[{}] Axum服务器绑定到: {}", label, self.config.addr);
+        let tls = match self.config.tls.clone() {
+            None => None,
+            #[cfg(feature = "tls")]
+            Some(tls) => {
+                let tls = server::ReloadableTlsConfig::new(tls, self.config.http2)?;
+                tls.clone().spawn_watcher(self.shutdown_trigger.clone());
+                Some(tls)
+            }
+            #[cfg(not(feature = "tls"))]
+            Some(_) => baizekit_app::anyhow::bail!("[{}] TLS已配置，但未启用 baizekit-api 的 tls feature", label),
+        };
+
+        let mut listeners = Vec::with_capacity(self.routers.len());
+        for ListenerRouter { name, conf, router } in &self.routers {
+            let listener = Listener::bind(conf)
+                .await
+                .with_context(|| format!("[{}] 监听器 {} 绑定失败", label, name))?;
+            let tls = match conf.tls {
+                false => None,
+                true if !listener.is_tcp() => {
+                    baizekit_app::anyhow::bail!("[{}] 监听器 {} 不是 TCP 监听器，无法启用 TLS", label, name)
+                }
+                true => Some(
+                    tls.clone()
+                        .with_context(|| format!("[{}] 监听器 {} 启用了 TLS，但未配置 tls", label, name))?,
+                ),
+            };
+
+            let scheme = match (listener.is_tcp(), tls.is_some()) {
+                (false, _) => "http+unix",
+                (true, true) => "https",
+                (true, false) => "http",
+            };
+            info!("[{}] Axum服务器 {} 绑定到: {}://{}", label, name, scheme, listener);
+            listeners.push((listener, router.clone(), tls));
+        }
 
         self.print_service_info();
 
         let shutdown_trigger = self.shutdown_trigger.clone();
         let shutdown_done = self.shutdown_done.clone();
-        let router = self.router.clone();
+        let config = self.config.clone();
 
         tokio::spawn(async move {
-            let server = axum::serve(listener, router).with_graceful_shutdown(async move {
-                shutdown_trigger.cancelled().await;
-                info!("Axum服务器开始优雅关闭...");
-                shutdown_done.cancel();
-            });
+            let mut servers = JoinSet::new();
+            for (listener, router, tls) in listeners {
+                servers.spawn(server::serve(listener, router, config.clone(), tls, shutdown_trigger.clone()));
+            }
+            servers.join_all().await;
 
-            let _ = server.await;
             info!("Axum服务器已完全关闭");
+            shutdown_done.cancel();
         });
 
         Ok(())
@@ -223,6 +875,11 @@ impl Component for AxumComponent {
 
     async fn shutdown(&self) -> Result<()> {
         info!("收到关闭信号，通知Axum服务器关闭...");
+        // 升级后的连接不受服务器优雅关闭管理，需单独关闭
+        #[cfg(feature = "ws")]
+        if let Some(hub) = &self.ws_hub {
+            hub.close_all();
+        }
         self.shutdown_trigger.cancel();
         self.shutdown_done.cancelled().await;
         info!("Axum组件已关闭");
@@ -231,21 +888,222 @@ impl Component for AxumComponent {
 }
 
 impl AxumComponent {
+    /// 路由表
+    pub fn routes(&self) -> &RouteRegistry {
+        &self.routes
+    }
+
     fn print_service_info(&self) {
-        let print_operation = |method: &str, path: &str, operation: Option<Operation>| {
-            let Some(operation) = operation else { return };
-            info!("{:>6} - {}: {}", method, path, operation.summary.unwrap_or_default());
+        for route in self.routes.routes().iter().filter(|route| route.service.is_some()) {
+            let summary = match (&route.summary, route.documented) {
+                (Some(summary), _) => summary.as_str(),
+                (None, true) => "",
+                (None, false) => "(undocumented)",
+            };
+            info!("{:>7} - {}: {}", route.method, route.path, summary);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_apply_server_layers() {
+        let conf = AxumComponentConfig {
+            body_limit: Some(8),
+            compression: vec![CompressionAlgorithm::Gzip],
+            response_headers: HashMap::from([("x-powered-by".to_string(), "baizekit".to_string())]),
+            ..Default::default()
         };
+        let router = Router::new()
+            .route("/large", get(|| async { "x".repeat(4096) }))
+            .route("/echo", post(|body: String| async move { body }));
+        let router = apply_server_layers(router, &conf).unwrap();
+
+        let req = Request::get("/large")
+            .header(ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(resp.headers()["x-powered-by"], "baizekit");
 
-        for (path, path_item) in self.openapi.paths.paths.clone() {
-            print_operation("GET", &path, path_item.get);
-            print_operation("POST", &path, path_item.post);
-            print_operation("PUT", &path, path_item.put);
-            print_operation("DELETE", &path, path_item.delete);
-            print_operation("PATCH", &path, path_item.patch);
-            print_operation("HEAD", &path, path_item.head);
-            print_operation("OPTIONS", &path, path_item.options);
-            print_operation("TRACE", &path, path_item.trace);
+        let req = Request::post("/echo").body(Body::from("0123456789")).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic_in_reply_hook() {
+        struct PanicHook;
+
+        impl crate::layer::ReplyHook for PanicHook {
+            fn extend(&self, _: &crate::layer::ReplyContext, _: &mut serde_json::Map<String, serde_json::Value>) {
+                panic!("hook failed")
+            }
         }
+
+        let router = Router::new().route("/user", get(|| async { crate::response::ApiOK::with_data("alice") }));
+        let service = AxumServiceInfo::new("/api", router, OpenApi::new(Info::new("", ""), Paths::new()));
+        let router = AxumComponentBuilder::new()
+            .add_service(service)
+            .with_reply_hook(Arc::new(PanicHook))
+            .build_router(DEFAULT_LISTENER, &AxumComponentConfig::default(), &Default::default())
+            .unwrap();
+
+        let req = Request::get("/api/user").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_invalid_response_header() {
+        let conf = AxumComponentConfig {
+            response_headers: HashMap::from([("bad header".to_string(), "v".to_string())]),
+            ..Default::default()
+        };
+        assert!(apply_server_layers(Router::new(), &conf).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_listener_services() {
+        let service = |path: &str| {
+            let router = Router::new().route("/ping", get(|| async { "pong" }));
+            AxumServiceInfo::new(path, router, OpenApi::new(Info::new("", ""), Paths::new()))
+        };
+        let builder = AxumComponentBuilder::new()
+            .add_service(service("/api"))
+            .add_service(service("/admin").on_listener("internal"));
+        let conf = AxumComponentConfig::default();
+
+        let status = |router: &Router, uri: &str| {
+            let req = Request::get(uri).body(Body::empty()).unwrap();
+            let router = router.clone();
+            async move { router.oneshot(req).await.unwrap().status() }
+        };
+
+        let public = builder.build_router(DEFAULT_LISTENER, &conf, &Default::default()).unwrap();
+        assert_eq!(status(&public, "/api/ping").await, StatusCode::OK);
+        assert_eq!(status(&public, "/admin/ping").await, StatusCode::NOT_FOUND);
+
+        let internal = builder.build_router("internal", &conf, &Default::default()).unwrap();
+        assert_eq!(status(&internal, "/api/ping").await, StatusCode::NOT_FOUND);
+        assert_eq!(status(&internal, "/admin/ping").await, StatusCode::OK);
+        assert_eq!(status(&internal, "/health").await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_effective_listeners() {
+        let conf: AxumComponentConfig = serde_json::from_value(serde_json::json!({
+            "addr": "127.0.0.1:8080",
+            "listeners": { "internal": { "path": "/run/app.sock", "mode": "660" } }
+        }))
+        .unwrap();
+
+        let listeners = conf.effective_listeners();
+        assert_eq!(listeners[DEFAULT_LISTENER].addr, Some(conf.addr));
+        assert_eq!(listeners["internal"].path.as_deref(), Some("/run/app.sock"));
+        assert_eq!(listeners["internal"].addr, None);
+    }
+
+    #[test]
+    fn test_service_tag() {
+        use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathsBuilder};
+
+        let paths = PathsBuilder::new()
+            .path("/users", PathItem::new(HttpMethod::Get, OperationBuilder::new().build()))
+            .path("/roles", PathItem::new(HttpMethod::Get, OperationBuilder::new().tag("role").build()))
+            .build();
+        let service = AxumServiceInfo::new("/admin", Router::new(), OpenApi::new(Info::new("", ""), paths))
+            .with_tag("admin", Some("管理后台".to_string()));
+
+        let openapi = service.tagged_openapi();
+        let tags = |path: &str| openapi.paths.paths[path].get.as_ref().unwrap().tags.clone().unwrap();
+        assert_eq!(tags("/users"), vec!["admin"]);
+        assert_eq!(tags("/roles"), vec!["role"]);
+        assert_eq!(openapi.tags.unwrap()[0].name, "admin");
+    }
+
+    #[tokio::test]
+    async fn test_route_registry() {
+        use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathsBuilder};
+
+        let operation = OperationBuilder::new().summary(Some("用户列表")).build();
+        let paths = PathsBuilder::new()
+            .path("/users", PathItem::new(HttpMethod::Get, operation))
+            .build();
+        let router = Router::new()
+            .route("/users", get(|| async { "[]" }))
+            .route("/internal/ping", get(|| async { "pong" }));
+        let service = AxumServiceInfo::new("/api", router, OpenApi::new(Info::new("", ""), paths))
+            .with_routes([(Method::GET, "/users"), (Method::GET, "/internal/ping")])
+            .with_route(Method::POST, "/internal/sync", || async { "OK" });
+        let builder = AxumComponentBuilder::new().add_service(service);
+        let conf =
+            AxumComponentConfig { routes: RoutesConfig { enabled: true, ..Default::default() }, ..Default::default() };
+
+        let registry = builder.route_registry(&conf).unwrap();
+        let users = registry.find(&Method::GET, "/api/users").unwrap();
+        assert_eq!(users.summary.as_deref(), Some("用户列表"));
+        assert!(users.documented);
+        assert_eq!(users.listeners, vec![DEFAULT_LISTENER]);
+        assert!(registry.find(&Method::GET, "/routes").is_some());
+
+        let undocumented: Vec<_> = registry
+            .undocumented()
+            .iter()
+            .map(|route| (route.method.as_str(), route.path.as_str()))
+            .collect();
+        assert_eq!(
+            undocumented,
+            vec![
+                ("GET", "/api/internal/ping"),
+                ("POST", "/api/internal/sync")
+            ]
+        );
+
+        let router = builder.build_router(DEFAULT_LISTENER, &conf, &Arc::new(registry)).unwrap();
+        let req = Request::post("/api/internal/sync").body(Body::empty()).unwrap();
+        assert_eq!(router.clone().oneshot(req).await.unwrap().status(), StatusCode::OK);
+
+        let req = Request::get("/routes").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let reply: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["data"]["routes"][0]["path"], "/api/users");
+    }
+
+    #[test]
+    fn test_route_registry_requires_declared_routes() {
+        let router = Router::new().route("/hidden", get(|| async { "OK" }));
+        let service = AxumServiceInfo::new("/api", router, OpenApi::new(Info::new("", ""), Paths::new()));
+        let builder = AxumComponentBuilder::new().add_service(service);
+        assert!(builder.route_registry(&AxumComponentConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_route_registry_merges_listeners() {
+        let route = |listeners: &[&str]| RouteInfo {
+            method: "GET".to_string(),
+            path: "/ping".to_string(),
+            service: None,
+            summary: None,
+            documented: false,
+            auth: Vec::new(),
+            listeners: listeners.iter().map(|name| name.to_string()).collect(),
+        };
+
+        let mut registry = RouteRegistry::default();
+        registry.push(route(&["a", "b"]));
+        registry.push(route(&["a", "c"]));
+        assert_eq!(registry.routes()[0].listeners, vec!["a", "b", "c"]);
     }
 }
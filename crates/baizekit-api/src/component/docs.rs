@@ -0,0 +1,362 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Response};
+use axum::{Json, Router};
+use baizekit_app::anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::server::ServerBuilder;
+use utoipa::openapi::{Components, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::layer::constant_time_eq;
+
+/// 接口文档配置，读取自 `axum.{label}.docs`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DocsConfig {
+    /// 是否暴露接口文档，生产环境建议关闭
+    pub enabled: bool,
+    /// 文档 UI
+    pub ui: DocsUi,
+    /// 文档 UI 路径
+    pub ui_path: String,
+    /// 文档 UI 的脚本地址，为空时使用固定版本的 CDN 地址，Swagger UI 不使用
+    pub ui_script_url: Option<String>,
+    /// 文档 UI 脚本的子资源完整性(SRI)校验值，例如 `sha384-...`
+    pub ui_script_integrity: Option<String>,
+    /// OpenAPI JSON 路径
+    pub openapi_path: String,
+    /// 暴露文档的监听器，为空时所有监听器都暴露
+    pub listeners: Vec<String>,
+    /// 访问文档所需的 Basic 认证
+    pub basic_auth: Option<DocsBasicAuth>,
+    /// OpenAPI `servers` 元数据
+    pub servers: Vec<DocsServer>,
+    /// OpenAPI 安全方案，键为方案名称
+    pub security_schemes: BTreeMap<String, DocsSecurityScheme>,
+    /// 全局生效的安全方案名称，任意一个满足即可
+    pub global_security: Vec<String>,
+    /// 构建时将合并后的 OpenAPI 写入该文件，便于生成客户端
+    pub dump_path: Option<String>,
+}
+
+impl Default for DocsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ui: DocsUi::default(),
+            ui_path: "/swagger-ui".to_string(),
+            ui_script_url: None,
+            ui_script_integrity: None,
+            openapi_path: "/api-docs/openapi.json".to_string(),
+            listeners: Vec::new(),
+            basic_auth: None,
+            servers: Vec::new(),
+            security_schemes: BTreeMap::new(),
+            global_security: Vec::new(),
+            dump_path: None,
+        }
+    }
+}
+
+/// 文档 UI 类型，除 Swagger UI 外均从 CDN 加载固定版本的前端资源
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocsUi {
+    #[default]
+    Swagger,
+    Redoc,
+    Scalar,
+    Rapidoc,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocsBasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocsServer {
+    pub url: String,
+    pub description: Option<String>,
+}
+
+/// OpenAPI 安全方案
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocsSecurityScheme {
+    /// `Authorization: Bearer <token>`
+    Bearer { bearer_format: Option<String> },
+    /// `Authorization: Basic <credentials>`
+    Basic,
+    /// 请求头中的 API Key
+    ApiKey { header: String },
+}
+
+impl From<&DocsSecurityScheme> for SecurityScheme {
+    fn from(scheme: &DocsSecurityScheme) -> Self {
+        match scheme {
+            DocsSecurityScheme::Bearer { bearer_format } => {
+                let mut http = HttpBuilder::new().scheme(HttpAuthScheme::Bearer);
+                if let Some(format) = bearer_format {
+                    http = http.bearer_format(format);
+                }
+                SecurityScheme::Http(http.build())
+            }
+            DocsSecurityScheme::Basic => SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Basic).build()),
+            DocsSecurityScheme::ApiKey { header } => SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(header))),
+        }
+    }
+}
+
+impl DocsConfig {
+    /// 指定监听器是否暴露文档
+    pub(crate) fn serves(&self, listener: &str) -> bool {
+        self.enabled && (self.listeners.is_empty() || self.listeners.iter().any(|name| name == listener))
+    }
+
+    /// 写入 `servers`、安全方案和全局安全要求
+    pub(crate) fn apply_metadata(&self, openapi: &mut OpenApi) -> Result<()> {
+        if !self.servers.is_empty() {
+            let servers = self.servers.iter().map(|server| {
+                ServerBuilder::new()
+                    .url(&server.url)
+                    .description(server.description.clone())
+                    .build()
+            });
+            openapi.servers = Some(servers.collect());
+        }
+
+        if !self.security_schemes.is_empty() {
+            let components = openapi.components.get_or_insert_with(Components::new);
+            for (name, scheme) in &self.security_schemes {
+                components.add_security_scheme(name, SecurityScheme::from(scheme));
+            }
+        }
+
+        for name in &self.global_security {
+            if !self.security_schemes.contains_key(name) {
+                baizekit_app::anyhow::bail!("global security scheme '{}' is not defined", name);
+            }
+            openapi
+                .security
+                .get_or_insert_with(Vec::new)
+                .push(SecurityRequirement::new(name, Vec::<String>::new()));
+        }
+
+        Ok(())
+    }
+
+    /// 将 OpenAPI 写入 `dump_path`
+    pub(crate) fn dump(&self, openapi: &OpenApi) -> Result<()> {
+        let Some(path) = &self.dump_path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = openapi.to_pretty_json()?;
+        std::fs::write(path, json).with_context(|| format!("failed to write openapi to '{}'", path))
+    }
+
+    /// 构建文档路由，包含 UI 和 OpenAPI JSON
+    pub(crate) fn router(&self, openapi: OpenApi) -> Router {
+        let router = match self.ui {
+            DocsUi::Swagger => {
+                Router::new().merge(SwaggerUi::new(self.ui_path.clone()).url(self.openapi_path.clone(), openapi))
+            }
+            ui => {
+                let script = self.ui_script_url.as_deref().unwrap_or(ui.script_url());
+                let html =
+                    Html(ui.html(&openapi.info.title, &self.openapi_path, script, self.ui_script_integrity.as_deref()));
+                Router::new()
+                    .route(&self.ui_path, axum::routing::get(move || async move { html }))
+                    .route(&self.openapi_path, axum::routing::get(move || async move { Json(openapi) }))
+            }
+        };
+
+        match &self.basic_auth {
+            None => router,
+            Some(auth) => {
+                let credentials = STANDARD.encode(format!("{}:{}", auth.username, auth.password));
+                router.layer(axum::middleware::from_fn_with_state(format!("Basic {}", credentials), basic_auth))
+            }
+        }
+    }
+}
+
+impl DocsUi {
+    /// 默认的 CDN 脚本地址，固定版本避免上游发布影响文档页面
+    fn script_url(self) -> &'static str {
+        match self {
+            DocsUi::Redoc => "https://cdn.redoc.ly/redoc/v2.1.5/bundles/redoc.standalone.js",
+            DocsUi::Scalar => "https://cdn.jsdelivr.net/npm/@scalar/api-reference@1.25.0",
+            DocsUi::Rapidoc => "https://unpkg.com/rapidoc@9.3.8/dist/rapidoc-min.js",
+            DocsUi::Swagger => "",
+        }
+    }
+
+    /// 页面中的配置值均经过 HTML 转义，标题或路径中的引号、尖括号不会破坏页面结构
+    fn html(self, title: &str, openapi_path: &str, script: &str, integrity: Option<&str>) -> String {
+        let (title, openapi_path, script) = (html_escape(title), html_escape(openapi_path), html_escape(script));
+        let integrity = match integrity {
+            Some(integrity) => format!(r#" integrity="{}" crossorigin="anonymous""#, html_escape(integrity)),
+            None => String::new(),
+        };
+        let body = match self {
+            DocsUi::Redoc => format!(
+                r#"<redoc spec-url="{}"></redoc>
+    <script src="{}"{}></script>"#,
+                openapi_path, script, integrity
+            ),
+            DocsUi::Scalar => format!(
+                r#"<script id="api-reference" data-url="{}"></script>
+    <script src="{}"{}></script>"#,
+                openapi_path, script, integrity
+            ),
+            DocsUi::Rapidoc => format!(
+                r#"<rapi-doc spec-url="{}"></rapi-doc>
+    <script type="module" src="{}"{}></script>"#,
+                openapi_path, script, integrity
+            ),
+            DocsUi::Swagger => String::new(),
+        };
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>{}</title>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+  </head>
+  <body>
+    {}
+  </body>
+</html>"#,
+            title, body
+        )
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+async fn basic_auth(State(expected): State<String>, req: Request, next: Next) -> Response {
+    let authorized = req
+        .headers()
+        .get(AUTHORIZATION)
+        .is_some_and(|value| constant_time_eq(value.as_bytes(), expected.as_bytes()));
+    if authorized {
+        return next.run(req).await;
+    }
+
+    (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, r#"Basic realm="docs""#)], "Unauthorized").into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use tower::ServiceExt;
+    use utoipa::openapi::{Info, Paths};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_docs_router_with_basic_auth() {
+        let conf = DocsConfig {
+            ui: DocsUi::Redoc,
+            ui_path: "/docs".to_string(),
+            ui_script_integrity: Some("sha384-test".to_string()),
+            basic_auth: Some(DocsBasicAuth { username: "admin".to_string(), password: "secret".to_string() }),
+            ..Default::default()
+        };
+        let router = conf.router(OpenApi::new(Info::new("App", "0.1.0"), Paths::new()));
+
+        let req = Request::get("/docs").body(Body::empty()).unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = Request::get("/docs")
+            .header(AUTHORIZATION, format!("Basic {}", STANDARD.encode("admin:wrong")))
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = Request::get("/docs")
+            .header(AUTHORIZATION, format!("Basic {}", STANDARD.encode("admin:secret")))
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains(
+            r#"<script src="https://cdn.redoc.ly/redoc/v2.1.5/bundles/redoc.standalone.js" integrity="sha384-test" crossorigin="anonymous"></script>"#
+        ));
+
+        let req = Request::get("/api-docs/openapi.json")
+            .header(AUTHORIZATION, format!("Basic {}", STANDARD.encode("admin:secret")))
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_apply_metadata_and_dump() {
+        let dir = tempfile::tempdir().unwrap();
+        let dump_path = dir.path().join("docs/openapi.json").to_string_lossy().to_string();
+        let conf: DocsConfig = serde_json::from_value(serde_json::json!({
+            "servers": [{ "url": "https://api.example.com" }],
+            "security_schemes": { "jwt": { "type": "bearer", "bearer_format": "JWT" } },
+            "global_security": ["jwt"],
+            "dump_path": dump_path,
+        }))
+        .unwrap();
+
+        let mut openapi = OpenApi::new(Info::new("App", "0.1.0"), Paths::new());
+        conf.apply_metadata(&mut openapi).unwrap();
+        conf.dump(&openapi).unwrap();
+
+        let dumped: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&dump_path).unwrap()).unwrap();
+        assert_eq!(dumped["servers"][0]["url"], "https://api.example.com");
+        assert_eq!(dumped["components"]["securitySchemes"]["jwt"]["scheme"], "bearer");
+        assert_eq!(dumped["security"][0]["jwt"], serde_json::json!([]));
+
+        let conf = DocsConfig { global_security: vec!["missing".to_string()], ..Default::default() };
+        assert!(conf.apply_metadata(&mut openapi).is_err());
+    }
+
+    #[test]
+    fn test_html_escapes_config_values() {
+        let html = DocsUi::Scalar.html("</title><script>alert(1)</script>", "/docs\"onload=\"x", "/s.js", None);
+        assert!(html.contains("<title>&lt;/title&gt;&lt;script&gt;alert(1)&lt;/script&gt;</title>"));
+        assert!(html.contains(r#"data-url="/docs&quot;onload=&quot;x""#));
+        assert!(!html.contains("<script>alert"));
+    }
+}
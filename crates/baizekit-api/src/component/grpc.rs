@@ -0,0 +1,477 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::State;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use baizekit_app::anyhow::{Context, Result};
+use baizekit_app::application::ApplicationInner;
+use baizekit_app::async_trait::async_trait;
+use baizekit_app::component::Component;
+use baizekit_app::config::Config;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+use tonic::body::Body;
+use tonic::server::NamedService;
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptorLayer;
+use tonic::{Request, Status};
+use tonic_health::ServingStatus;
+use tonic_health::server::HealthReporter;
+use tower::{Service, ServiceExt};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, RequestId, SetRequestIdLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use tracing::{Level, Span, info};
+
+use crate::component::axum::{AxumComponentConfig, deserialize_socket_addr};
+use crate::component::server::{self, Listener};
+
+/// gRPC 服务配置，读取自 `grpc.{label}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GrpcComponentConfig {
+    /// 服务器监听地址，格式：0.0.0.0:50051 或 `[::1]:50051`
+    #[serde(deserialize_with = "deserialize_socket_addr")]
+    pub addr: SocketAddr,
+    /// 是否单独监听 `addr`；为 false 时通过 [`crate::component::axum::AxumComponentBuilder::with_grpc`] 与 HTTP 共用端口
+    pub standalone: bool,
+    /// 是否注册 `grpc.health.v1.Health` 服务
+    pub health: bool,
+    /// 是否注册 `grpc.reflection.v1.ServerReflection` 服务
+    pub reflection: bool,
+    /// 请求超时时间(秒)，超时返回 `DEADLINE_EXCEEDED`；客户端的 `grpc-timeout` 更短时以其为准
+    pub request_timeout_seconds: Option<u64>,
+    /// 单个连接的并发请求上限(HTTP/2 并发流数)，仅单独监听时生效；
+    /// 共用端口时使用 `axum.{label}.http2_max_concurrent_streams`
+    pub concurrency_limit_per_connection: Option<u32>,
+    /// 优雅关闭等待时间(秒)，超时后强制关闭剩余连接
+    pub shutdown_timeout_seconds: u64,
+    /// 是否启用 TCP_NODELAY
+    pub tcp_nodelay: bool,
+    /// TCP keepalive 空闲时间(秒)，未配置时使用系统默认值
+    pub tcp_keepalive_seconds: Option<u64>,
+    /// 请求追踪的日志级别
+    pub trace_level: TraceLevel,
+}
+
+impl Default for GrpcComponentConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 50051),
+            standalone: true,
+            health: true,
+            reflection: true,
+            request_timeout_seconds: None,
+            concurrency_limit_per_connection: None,
+            shutdown_timeout_seconds: 30,
+            tcp_nodelay: true,
+            tcp_keepalive_seconds: None,
+            trace_level: TraceLevel::Info,
+        }
+    }
+}
+
+/// 请求追踪的日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<TraceLevel> for Level {
+    fn from(level: TraceLevel) -> Self {
+        match level {
+            TraceLevel::Trace => Level::TRACE,
+            TraceLevel::Debug => Level::DEBUG,
+            TraceLevel::Info => Level::INFO,
+            TraceLevel::Warn => Level::WARN,
+            TraceLevel::Error => Level::ERROR,
+        }
+    }
+}
+
+type DynInterceptor = Arc<dyn Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync>;
+
+/// 依次执行注册的拦截器，任一拦截器返回错误时终止请求
+#[derive(Clone, Default)]
+struct Interceptors(Arc<Vec<DynInterceptor>>);
+
+impl Interceptor for Interceptors {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        for interceptor in self.0.iter() {
+            request = interceptor(request)?;
+        }
+        Ok(request)
+    }
+}
+
+pub struct GrpcComponentBuilder {
+    router: Router,
+    services: Vec<&'static str>,
+    file_descriptor_sets: Vec<&'static [u8]>,
+    interceptors: Vec<DynInterceptor>,
+}
+
+impl Default for GrpcComponentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrpcComponentBuilder {
+    pub fn new() -> Self {
+        Self { router: Router::new(), services: Vec::new(), file_descriptor_sets: Vec::new(), interceptors: Vec::new() }
+    }
+
+    /// 添加 tonic 生成的服务，例如 `GreeterServer::new(greeter)`；健康检查中该服务的状态随组件启停变化
+    pub fn add_service<S>(mut self, service: S) -> Self
+    where
+        S: Service<axum::http::Request<Body>, Error = Infallible> + NamedService + Clone + Send + Sync + 'static,
+        S::Response: IntoResponse,
+        S::Future: Send + 'static,
+    {
+        self.router = route_grpc_service(self.router, service);
+        self.services.push(S::NAME);
+        self
+    }
+
+    /// 注册反射服务使用的文件描述符集，通常为 `tonic::include_file_descriptor_set!` 的结果
+    pub fn with_file_descriptor_set(mut self, file_descriptor_set: &'static [u8]) -> Self {
+        self.file_descriptor_sets.push(file_descriptor_set);
+        self
+    }
+
+    /// 添加拦截器，按添加顺序执行，可用于鉴权或读取元数据
+    pub fn with_interceptor<F>(mut self, interceptor: F) -> Self
+    where
+        F: Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync + 'static,
+    {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    pub async fn build(self, inner: Arc<ApplicationInner>, label: String) -> Result<GrpcComponent> {
+        let config = inner.config().await;
+        let conf: GrpcComponentConfig = config.get(format!("grpc.{}", label).as_str())?;
+        self.build_with_config(conf)
+    }
+
+    fn build_with_config(self, conf: GrpcComponentConfig) -> Result<GrpcComponent> {
+        if !conf.standalone && conf.concurrency_limit_per_connection.is_some() {
+            baizekit_app::anyhow::bail!(
+                "gRPC 与 HTTP 共用端口时不支持 concurrency_limit_per_connection，请配置 axum 的 http2_max_concurrent_streams"
+            );
+        }
+        let mut router = self.router;
+
+        let health = match conf.health {
+            true => {
+                let (reporter, service) = tonic_health::server::health_reporter();
+                router = route_grpc_service(router, service);
+                Some(reporter)
+            }
+            false => None,
+        };
+
+        if conf.reflection {
+            let mut builder = tonic_reflection::server::Builder::configure();
+            if conf.health {
+                builder = builder.register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET);
+            }
+            for file_descriptor_set in &self.file_descriptor_sets {
+                builder = builder.register_encoded_file_descriptor_set(file_descriptor_set);
+            }
+            let service = builder.build_v1().context("构建 gRPC 反射服务失败")?;
+            router = route_grpc_service(router, service);
+        }
+
+        if !self.interceptors.is_empty() {
+            router = router.layer(InterceptorLayer::new(Interceptors(Arc::new(self.interceptors))));
+        }
+
+        // 作为路由中间件，共用端口时同样生效
+        let timeout = conf.request_timeout_seconds.map(Duration::from_secs);
+        router = router.layer(axum::middleware::from_fn_with_state(timeout, grpc_timeout));
+
+        let level = Level::from(conf.trace_level);
+        router = router
+            .layer(
+                TraceLayer::new_for_grpc()
+                    .make_span_with(make_span as fn(&axum::http::Request<axum::body::Body>) -> Span)
+                    .on_response(DefaultOnResponse::new().level(level)),
+            )
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
+
+        let shutdown_token = CancellationToken::new();
+        Ok(GrpcComponent {
+            router,
+            services: self.services,
+            health,
+            config: conf,
+            shutdown_trigger: shutdown_token.child_token(),
+            shutdown_done: shutdown_token,
+        })
+    }
+}
+
+/// 按 `/{package.Service}/{Method}` 挂载服务，与 tonic 的 `Routes` 一致但不设置 fallback，便于与 HTTP 路由合并
+fn route_grpc_service<S>(router: Router, service: S) -> Router
+where
+    S: Service<axum::http::Request<Body>, Error = Infallible> + NamedService + Clone + Send + Sync + 'static,
+    S::Response: IntoResponse,
+    S::Future: Send + 'static,
+{
+    let path = format!("/{}/{{*rest}}", S::NAME);
+    router.route_service(&path, service.map_request(|req: axum::http::Request<axum::body::Body>| req.map(Body::new)))
+}
+
+/// 请求超时，客户端的 `grpc-timeout` 更短时以其为准，超时返回 `DEADLINE_EXCEEDED`
+async fn grpc_timeout(
+    State(timeout): State<Option<Duration>>,
+    req: axum::extract::Request,
+    next: Next,
+) -> axum::response::Response {
+    let client_timeout = req
+        .headers()
+        .get("grpc-timeout")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_grpc_timeout);
+    let timeout = match (timeout, client_timeout) {
+        (Some(a), Some(b)) => a.min(b),
+        (Some(t), None) | (None, Some(t)) => t,
+        (None, None) => return next.run(req).await,
+    };
+
+    match tokio::time::timeout(timeout, next.run(req)).await {
+        Ok(resp) => resp,
+        Err(_) => Status::deadline_exceeded(format!("request timed out after {:?}", timeout)).into_http(),
+    }
+}
+
+/// 解析 `grpc-timeout`，格式为最多 8 位数字加单位，例如 `100m`
+fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let (value, unit) = value.split_at(value.len().checked_sub(1)?);
+    if value.is_empty() || value.len() > 8 {
+        return None;
+    }
+    let value: u64 = value.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(value * 3600)),
+        "M" => Some(Duration::from_secs(value * 60)),
+        "S" => Some(Duration::from_secs(value)),
+        "m" => Some(Duration::from_millis(value)),
+        "u" => Some(Duration::from_micros(value)),
+        "n" => Some(Duration::from_nanos(value)),
+        _ => None,
+    }
+}
+
+/// 请求 span，属性遵循 OpenTelemetry RPC 语义约定
+fn make_span(request: &axum::http::Request<axum::body::Body>) -> Span {
+    let x_request_id = request.extensions().get::<RequestId>();
+    let path = request.uri().path().trim_start_matches('/');
+    let (service, method) = path.split_once('/').unwrap_or((path, ""));
+    tracing::info_span!(
+        "grpc",
+        otel.name = path,
+        otel.kind = "server",
+        ?x_request_id,
+        rpc.system = "grpc",
+        rpc.service = service,
+        rpc.method = method,
+    )
+}
+
+/// 读取请求 ID，由组件的 `x-request-id` 中间件生成或沿用上游传入的值
+pub fn grpc_request_id<T>(request: &Request<T>) -> Option<&str> {
+    request.metadata().get("x-request-id").and_then(|v| v.to_str().ok())
+}
+
+pub struct GrpcComponent {
+    router: Router,
+    services: Vec<&'static str>,
+    health: Option<HealthReporter>,
+    config: GrpcComponentConfig,
+    shutdown_trigger: CancellationToken,
+    shutdown_done: CancellationToken,
+}
+
+impl GrpcComponent {
+    /// gRPC 路由，用于通过 [`crate::component::axum::AxumComponentBuilder::with_grpc`] 与 HTTP 共用端口
+    pub fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    /// 健康检查状态，未启用健康检查时为 None
+    pub fn health_reporter(&self) -> Option<&HealthReporter> {
+        self.health.as_ref()
+    }
+
+    async fn set_serving_status(&self, status: ServingStatus) {
+        let Some(reporter) = &self.health else {
+            return;
+        };
+        reporter.set_service_status("", status).await;
+        for service in &self.services {
+            reporter.set_service_status(*service, status).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Component for GrpcComponent {
+    async fn init(&mut self, _config: &Config, label: String) -> Result<()> {
+        self.set_serving_status(ServingStatus::Serving).await;
+        for service in &self.services {
+            info!("[{}] gRPC服务: {}", label, service);
+        }
+
+        if !self.config.standalone {
+            info!("[{}] gRPC服务未单独监听，需挂载到Axum组件", label);
+            self.shutdown_done.cancel();
+            return Ok(());
+        }
+
+        let listener = tokio::net::TcpListener::bind(self.config.addr)
+            .await
+            .with_context(|| format!("[{}] gRPC服务器绑定失败: {}", label, self.config.addr))?;
+        info!("[{}] gRPC服务器绑定到: {}", label, self.config.addr);
+
+        // 与 HTTP 共用连接处理，优雅关闭超时后中止剩余连接
+        let conf = AxumComponentConfig {
+            shutdown_timeout_seconds: self.config.shutdown_timeout_seconds,
+            http2: true,
+            http2_max_concurrent_streams: self.config.concurrency_limit_per_connection,
+            tcp_nodelay: self.config.tcp_nodelay,
+            tcp_keepalive_seconds: self.config.tcp_keepalive_seconds,
+            ..Default::default()
+        };
+        let router = self.router.clone().fallback(unimplemented);
+        let shutdown_trigger = self.shutdown_trigger.clone();
+        let shutdown_done = self.shutdown_done.clone();
+        tokio::spawn(async move {
+            server::serve(Listener::Tcp(listener), router, conf, None, shutdown_trigger).await;
+            info!("gRPC服务器已完全关闭");
+            shutdown_done.cancel();
+        });
+
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        info!("收到关闭信号，通知gRPC服务器关闭...");
+        self.set_serving_status(ServingStatus::NotServing).await;
+        self.shutdown_trigger.cancel();
+        self.shutdown_done.cancelled().await;
+        info!("gRPC组件已关闭");
+        Ok(())
+    }
+}
+
+async fn unimplemented() -> axum::response::Response {
+    Status::unimplemented("").into_http()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::CONTENT_TYPE;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn health_check(service: &str) -> axum::http::Request<axum::body::Body> {
+        // HealthCheckRequest { service }，gRPC 帧：1 字节压缩标志 + 4 字节长度
+        let mut message = Vec::new();
+        if !service.is_empty() {
+            message.push(0x0a);
+            message.push(service.len() as u8);
+            message.extend_from_slice(service.as_bytes());
+        }
+        let mut frame = vec![0];
+        frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&message);
+
+        axum::http::Request::post("/grpc.health.v1.Health/Check")
+            .header(CONTENT_TYPE, "application/grpc")
+            .body(axum::body::Body::from(frame))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_and_interceptor() {
+        let component = GrpcComponentBuilder::new()
+            .build_with_config(GrpcComponentConfig::default())
+            .unwrap();
+        component.set_serving_status(ServingStatus::Serving).await;
+
+        let resp = component.router().oneshot(health_check("")).await.unwrap();
+        assert!(resp.headers().contains_key("x-request-id"));
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        // HealthCheckResponse { status: SERVING }
+        assert_eq!(body.as_ref(), &[0, 0, 0, 0, 2, 0x08, 0x01]);
+
+        let component = GrpcComponentBuilder::new()
+            .with_interceptor(|req| match req.metadata().get("authorization") {
+                Some(_) => Ok(req),
+                None => Err(Status::unauthenticated("missing token")),
+            })
+            .build_with_config(GrpcComponentConfig::default())
+            .unwrap();
+        let resp = component.router().oneshot(health_check("")).await.unwrap();
+        assert_eq!(resp.headers()["grpc-status"], "16");
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        assert_eq!(parse_grpc_timeout("100m"), Some(Duration::from_millis(100)));
+        assert_eq!(parse_grpc_timeout("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_grpc_timeout("123456789S"), None);
+        assert_eq!(parse_grpc_timeout("m"), None);
+
+        let router = Router::new()
+            .route("/slow.Service/Call", axum::routing::post(std::future::pending::<()>))
+            .layer(axum::middleware::from_fn_with_state(Some(Duration::from_secs(60)), grpc_timeout));
+        let req = axum::http::Request::post("/slow.Service/Call")
+            .header("grpc-timeout", "10m")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let resp = tokio::time::timeout(Duration::from_secs(5), router.oneshot(req))
+            .await
+            .unwrap()
+            .unwrap();
+        // DEADLINE_EXCEEDED
+        assert_eq!(resp.headers()["grpc-status"], "4");
+
+        let conf =
+            GrpcComponentConfig { standalone: false, concurrency_limit_per_connection: Some(10), ..Default::default() };
+        assert!(GrpcComponentBuilder::new().build_with_config(conf).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_same_port() {
+        use crate::component::axum::{AxumComponentBuilder, AxumComponentConfig, DEFAULT_LISTENER};
+
+        let component = GrpcComponentBuilder::new()
+            .build_with_config(GrpcComponentConfig { standalone: false, ..Default::default() })
+            .unwrap();
+        let router = AxumComponentBuilder::new()
+            .with_grpc(component.router())
+            .build_router(DEFAULT_LISTENER, &AxumComponentConfig::default(), &Default::default())
+            .unwrap();
+
+        let resp = router.clone().oneshot(health_check("")).await.unwrap();
+        assert_eq!(resp.headers()[CONTENT_TYPE], "application/grpc");
+
+        let req = axum::http::Request::get("/health").body(axum::body::Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+    }
+}
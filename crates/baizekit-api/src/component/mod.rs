@@ -1 +1,9 @@
-pub mod axum;
\ No newline at end of file
+pub mod axum;
+mod docs;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+mod routes;
+mod server;
+mod static_files;
+#[cfg(feature = "tls")]
+mod tls;
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use axum::Router;
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+use utoipa::openapi::OpenApi;
+use utoipa::openapi::path::{HttpMethod, Operation, PathItem};
+use utoipa::openapi::security::SecurityRequirement;
+
+use crate::response::ApiOK;
+
+/// 路由表端点配置，读取自 `axum.{label}.routes`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RoutesConfig {
+    /// 是否暴露路由表端点
+    pub enabled: bool,
+    /// 路由表端点路径
+    pub path: String,
+    /// 暴露路由表端点的监听器，为空时所有监听器都暴露，例如 `["internal"]`
+    pub listeners: Vec<String>,
+}
+
+impl Default for RoutesConfig {
+    fn default() -> Self {
+        Self { enabled: false, path: "/routes".to_string(), listeners: Vec::new() }
+    }
+}
+
+impl RoutesConfig {
+    pub(crate) fn serves(&self, listener: &str) -> bool {
+        self.enabled && (self.listeners.is_empty() || self.listeners.iter().any(|name| name == listener))
+    }
+}
+
+/// 路由信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct RouteInfo {
+    /// HTTP 方法
+    pub method: String,
+    /// 完整路由模板，例如 `/api/users/{id}`
+    pub path: String,
+    /// 所属服务的挂载路径，内置路由为空
+    pub service: Option<String>,
+    /// 接口摘要，取自 OpenAPI
+    pub summary: Option<String>,
+    /// 是否有 OpenAPI 文档
+    pub documented: bool,
+    /// 需要满足的安全方案名称，任意一个满足即可
+    pub auth: Vec<String>,
+    /// 路由所在的监听器
+    pub listeners: Vec<String>,
+}
+
+/// 路由表，由 [`crate::component::axum::AxumComponentBuilder::route_registry`] 构建
+///
+/// 收录服务 OpenAPI 中的接口、服务声明的路由和内置路由。
+/// axum 不支持读取 `Router` 中的路由，服务需要通过 [`crate::component::axum::AxumServiceInfo::with_routes`] 声明路由。
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RouteRegistry {
+    routes: Vec<RouteInfo>,
+}
+
+impl RouteRegistry {
+    pub fn routes(&self) -> &[RouteInfo] {
+        &self.routes
+    }
+
+    pub fn find(&self, method: &Method, path: &str) -> Option<&RouteInfo> {
+        self.routes
+            .iter()
+            .find(|route| route.method == method.as_str() && route.path == path)
+    }
+
+    /// 服务中没有 OpenAPI 文档的路由，内置路由不计入
+    pub fn undocumented(&self) -> Vec<&RouteInfo> {
+        self.routes
+            .iter()
+            .filter(|route| route.service.is_some() && !route.documented)
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, route: RouteInfo) {
+        match self
+            .routes
+            .iter_mut()
+            .find(|r| r.method == route.method && r.path == route.path)
+        {
+            Some(existing) => {
+                for listener in route.listeners {
+                    if !existing.listeners.contains(&listener) {
+                        existing.listeners.push(listener);
+                    }
+                }
+            }
+            None => self.routes.push(route),
+        }
+    }
+
+    /// 添加服务的路由：先添加 OpenAPI 中的接口，再补充声明的路由中未文档化的部分
+    pub(crate) fn add_service(
+        &mut self,
+        service: &str,
+        openapi: &OpenApi,
+        routes: &[(Method, String)],
+        listeners: &[String],
+    ) {
+        for (path, path_item) in &openapi.paths.paths {
+            for (method, operation) in operations(path_item) {
+                let security = operation.security.as_ref().or(openapi.security.as_ref());
+                self.push(RouteInfo {
+                    method: method.to_string(),
+                    path: join_path(service, path),
+                    service: Some(service.to_string()),
+                    summary: operation.summary.clone(),
+                    documented: true,
+                    auth: security.map(|s| security_names(s)).unwrap_or_default(),
+                    listeners: listeners.to_vec(),
+                });
+            }
+        }
+
+        for (method, path) in routes {
+            self.push(RouteInfo {
+                method: method.to_string(),
+                path: join_path(service, path),
+                service: Some(service.to_string()),
+                summary: None,
+                documented: false,
+                auth: Vec::new(),
+                listeners: listeners.to_vec(),
+            });
+        }
+    }
+
+    /// 添加内置路由，例如 `/health`
+    pub(crate) fn add_builtin(&mut self, path: &str, listeners: &[String]) {
+        self.push(RouteInfo {
+            method: Method::GET.to_string(),
+            path: path.to_string(),
+            service: None,
+            summary: None,
+            documented: false,
+            auth: Vec::new(),
+            listeners: listeners.to_vec(),
+        });
+    }
+
+    /// 路由表端点
+    pub(crate) fn router(self: Arc<Self>, path: &str) -> Router {
+        let handler = move || async move { ApiOK::with_data(RoutesReply { routes: self.routes.clone() }) };
+        Router::new().route(path, axum::routing::get(handler))
+    }
+}
+
+/// 路由表端点的响应
+#[derive(Serialize)]
+struct RoutesReply {
+    routes: Vec<RouteInfo>,
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    format!("{}{}", prefix.trim_end_matches('/'), path)
+}
+
+fn operations(path_item: &PathItem) -> impl Iterator<Item = (Method, &Operation)> {
+    [
+        (HttpMethod::Get, &path_item.get),
+        (HttpMethod::Put, &path_item.put),
+        (HttpMethod::Post, &path_item.post),
+        (HttpMethod::Delete, &path_item.delete),
+        (HttpMethod::Options, &path_item.options),
+        (HttpMethod::Head, &path_item.head),
+        (HttpMethod::Patch, &path_item.patch),
+        (HttpMethod::Trace, &path_item.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| Some((to_method(method), operation.as_ref()?)))
+}
+
+fn to_method(method: HttpMethod) -> Method {
+    match method {
+        HttpMethod::Get => Method::GET,
+        HttpMethod::Put => Method::PUT,
+        HttpMethod::Post => Method::POST,
+        HttpMethod::Delete => Method::DELETE,
+        HttpMethod::Options => Method::OPTIONS,
+        HttpMethod::Head => Method::HEAD,
+        HttpMethod::Patch => Method::PATCH,
+        HttpMethod::Trace => Method::TRACE,
+    }
+}
+
+/// `SecurityRequirement` 未公开方案名称，通过序列化结果读取
+fn security_names(requirements: &[SecurityRequirement]) -> Vec<String> {
+    let mut names: Vec<String> = requirements
+        .iter()
+        .filter_map(|requirement| serde_json::to_value(requirement).ok())
+        .filter_map(|value| value.as_object().map(|obj| obj.keys().cloned().collect::<Vec<_>>()))
+        .flatten()
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
@@ -0,0 +1,344 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, Request};
+use baizekit_app::anyhow::{Context, Result};
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::{GracefulShutdown, Watcher};
+use hyper_util::service::TowerToHyperService;
+use socket2::{SockRef, TcpKeepalive};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tower::ServiceExt;
+use tracing::{debug, info, warn};
+
+use crate::component::axum::{AxumComponentConfig, ListenerConfig};
+#[cfg(feature = "tls")]
+pub(crate) use crate::component::tls::ReloadableTlsConfig;
+
+/// TLS 握手超时时间
+#[cfg(feature = "tls")]
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 未启用 `tls` feature 时的占位类型
+#[cfg(not(feature = "tls"))]
+pub(crate) enum ReloadableTlsConfig {}
+
+/// 已绑定的监听器
+pub(crate) enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, std::path::PathBuf),
+}
+
+enum Accepted {
+    Tcp(TcpStream, SocketAddr),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+impl Listener {
+    pub(crate) async fn bind(conf: &ListenerConfig) -> Result<Self> {
+        match (&conf.addr, &conf.path) {
+            (Some(addr), None) => {
+                let listener = TcpListener::bind(addr).await.context("listener bind failed.")?;
+                Ok(Listener::Tcp(listener))
+            }
+            #[cfg(unix)]
+            (None, Some(path)) => Self::bind_unix(path, conf.mode.as_deref()),
+            #[cfg(not(unix))]
+            (None, Some(_)) => baizekit_app::anyhow::bail!("unix socket listener is not supported on this platform"),
+            _ => baizekit_app::anyhow::bail!("listener requires exactly one of `addr` or `path`"),
+        }
+    }
+
+    #[cfg(unix)]
+    fn bind_unix(path: &str, mode: Option<&str>) -> Result<Self> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        let path = std::path::PathBuf::from(path);
+        // 清理上次未正常退出遗留的 socket 文件
+        if path.metadata().map(|m| m.file_type().is_socket()).unwrap_or(false) {
+            std::fs::remove_file(&path).with_context(|| format!("failed to remove stale socket {:?}", path))?;
+        }
+
+        let listener = tokio::net::UnixListener::bind(&path).with_context(|| format!("failed to bind {:?}", path))?;
+        if let Some(mode) = mode {
+            let mode = u32::from_str_radix(mode, 8).with_context(|| format!("invalid unix socket mode '{}'", mode))?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+        }
+        Ok(Listener::Unix(listener, path))
+    }
+
+    pub(crate) fn is_tcp(&self) -> bool {
+        matches!(self, Listener::Tcp(_))
+    }
+
+    async fn accept(&self) -> io::Result<Accepted> {
+        match self {
+            Listener::Tcp(listener) => listener.accept().await.map(|(stream, addr)| Accepted::Tcp(stream, addr)),
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => listener.accept().await.map(|(stream, _)| Accepted::Unix(stream)),
+        }
+    }
+}
+
+impl Display for Listener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Listener::Tcp(listener) => match listener.local_addr() {
+                Ok(addr) => write!(f, "{}", addr),
+                Err(_) => write!(f, "tcp"),
+            },
+            #[cfg(unix)]
+            Listener::Unix(_, path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Listener::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// 接受连接并提供服务，直到 `shutdown` 被触发后优雅关闭
+#[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+pub(crate) async fn serve(
+    listener: Listener,
+    router: Router,
+    conf: AxumComponentConfig,
+    tls: Option<Arc<ReloadableTlsConfig>>,
+    shutdown: CancellationToken,
+) {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    if !conf.http2 {
+        builder = builder.http1_only();
+    }
+    if let Some(max) = conf.http2_max_concurrent_streams {
+        builder.http2().max_concurrent_streams(max);
+    }
+
+    let graceful = GracefulShutdown::new();
+    // 保存连接任务，优雅关闭超时后强制中止
+    let mut connections = JoinSet::new();
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    // 文件句柄耗尽等错误，短暂等待后重试
+                    warn!("accept connection failed: {}", err);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            // 回收已结束的连接任务
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            _ = shutdown.cancelled() => break,
+        };
+
+        let (stream, remote_addr) = match accepted {
+            Accepted::Tcp(stream, remote_addr) => (stream, remote_addr),
+            #[cfg(unix)]
+            Accepted::Unix(stream) => {
+                let conn = Connection::new(&builder, &router, &graceful, listener.to_string());
+                connections.spawn(conn.serve(stream, Extensions::new()));
+                continue;
+            }
+        };
+
+        configure_tcp_stream(&stream, &conf);
+
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(remote_addr));
+        let conn = Connection::new(&builder, &router, &graceful, remote_addr.to_string());
+
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &tls {
+            let acceptor = tls.acceptor();
+            connections.spawn(async move {
+                let stream = match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(err)) => return debug!("TLS handshake with {} failed: {}", remote_addr, err),
+                    Err(_) => return debug!("TLS handshake with {} timed out", remote_addr),
+                };
+                if let Some(cert) = crate::component::tls::client_certificate(&stream) {
+                    extensions.insert(cert);
+                }
+                conn.serve(stream, extensions).await;
+            });
+            continue;
+        }
+
+        connections.spawn(conn.serve(stream, extensions));
+    }
+
+    let name = listener.to_string();
+    drop(listener);
+    info!("服务器 {} 开始优雅关闭...", name);
+
+    let timeout = Duration::from_secs(conf.shutdown_timeout_seconds);
+    if tokio::time::timeout(timeout, graceful.shutdown()).await.is_err() {
+        warn!("服务器 {} 优雅关闭超时({:?})，强制关闭剩余{}个连接", name, timeout, connections.len());
+        connections.abort_all();
+    }
+    while connections.join_next().await.is_some() {}
+}
+
+/// 受优雅关闭管理的连接
+struct Connection {
+    builder: auto::Builder<TokioExecutor>,
+    router: Router,
+    watcher: Watcher,
+    peer: String,
+}
+
+impl Connection {
+    fn new(builder: &auto::Builder<TokioExecutor>, router: &Router, graceful: &GracefulShutdown, peer: String) -> Self {
+        Self { builder: builder.clone(), router: router.clone(), watcher: graceful.watcher(), peer }
+    }
+
+    /// 在连接上提供服务，`extensions` 会注入到该连接的每个请求中
+    async fn serve<I>(self, io: I, extensions: Extensions)
+    where
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let service = self.router.map_request(move |mut req: Request<Incoming>| {
+            req.extensions_mut().extend(extensions.clone());
+            req
+        });
+        let conn = self
+            .builder
+            .serve_connection_with_upgrades(TokioIo::new(io), TowerToHyperService::new(service));
+
+        if let Err(err) = self.watcher.watch(conn.into_owned()).await {
+            debug!("connection {} closed with error: {}", self.peer, err);
+        }
+    }
+}
+
+fn configure_tcp_stream(stream: &TcpStream, conf: &AxumComponentConfig) {
+    if let Err(err) = stream.set_nodelay(conf.tcp_nodelay) {
+        warn!("set TCP_NODELAY failed: {}", err);
+    }
+
+    if let Some(secs) = conf.tcp_keepalive_seconds {
+        let keepalive = TcpKeepalive::new().with_time(Duration::from_secs(secs));
+        if let Err(err) = SockRef::from(stream).set_tcp_keepalive(&keepalive) {
+            warn!("set TCP keepalive failed: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_and_graceful_shutdown() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = Listener::Tcp(listener);
+        // 处理函数持有 `hanging_tx`，被中止时 `hanging_rx` 收到关闭通知
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel::<()>();
+        let (hanging_tx, hanging_rx) = tokio::sync::oneshot::channel::<()>();
+        let hanging = Arc::new(std::sync::Mutex::new(Some((started_tx, hanging_tx))));
+        let router = Router::new()
+            .route(
+                "/ip",
+                get(|ConnectInfo(remote): ConnectInfo<std::net::SocketAddr>| async move { remote.ip().to_string() }),
+            )
+            .route(
+                "/hang",
+                get(move || {
+                    let (started_tx, hanging_tx) = hanging.lock().unwrap().take().unwrap();
+                    async move {
+                        let _hanging_tx = hanging_tx;
+                        let _ = started_tx.send(());
+                        std::future::pending::<()>().await
+                    }
+                }),
+            );
+        let conf = AxumComponentConfig {
+            http2: false,
+            tcp_keepalive_seconds: Some(60),
+            shutdown_timeout_seconds: 1,
+            ..Default::default()
+        };
+        let shutdown = CancellationToken::new();
+        let server = tokio::spawn(serve(listener, router, conf, None, shutdown.clone()));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /ip HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+        assert!(resp.ends_with("127.0.0.1"));
+
+        // 永不结束的请求在优雅关闭超时后被中止
+        let mut hanging_stream = TcpStream::connect(addr).await.unwrap();
+        hanging_stream
+            .write_all(b"GET /hang HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        started_rx.await.unwrap();
+
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+        assert!(hanging_rx.await.is_err());
+        let mut buf = Vec::new();
+        assert_eq!(hanging_stream.read_to_end(&mut buf).await.unwrap(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serve_unix_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.sock");
+        let conf = ListenerConfig {
+            path: Some(path.to_string_lossy().to_string()),
+            mode: Some("660".to_string()),
+            ..Default::default()
+        };
+        let listener = Listener::bind(&conf).await.unwrap();
+        assert_eq!(path.metadata().unwrap().permissions().mode() & 0o777, 0o660);
+
+        let router = Router::new().route("/health", get(|| async { "OK" }));
+        let shutdown = CancellationToken::new();
+        let server = tokio::spawn(serve(listener, router, AxumComponentConfig::default(), None, shutdown.clone()));
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::header::{
+    ACCEPT, ACCEPT_ENCODING, ALLOW, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY,
+};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use baizekit_app::async_trait::async_trait;
+use serde::Deserialize;
+
+/// 静态资源配置，读取自 `axum.{label}.static_files`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// 目录请求和 SPA 回退使用的首页文件
+    pub index: String,
+    /// 未找到文件时是否回退到首页，仅对接受 HTML 且路径无扩展名的请求生效
+    pub spa_fallback: bool,
+    /// 普通资源的 `Cache-Control`
+    pub cache_control: String,
+    /// 首页的 `Cache-Control`，默认每次协商，保证发布后及时生效
+    pub index_cache_control: String,
+    /// 是否按 `Accept-Encoding` 优先返回预压缩的 `.br`、`.gz` 文件
+    pub precompressed: bool,
+    /// 是否返回 `ETag` 并处理 `If-None-Match`
+    pub etag: bool,
+    /// 提供静态资源的监听器，为空时所有监听器都提供
+    pub listeners: Vec<String>,
+}
+
+impl Default for StaticFilesConfig {
+    fn default() -> Self {
+        Self {
+            index: "index.html".to_string(),
+            spa_fallback: true,
+            cache_control: "public, max-age=3600".to_string(),
+            index_cache_control: "no-cache".to_string(),
+            precompressed: true,
+            etag: true,
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl StaticFilesConfig {
+    pub(crate) fn serves(&self, listener: &str) -> bool {
+        self.listeners.is_empty() || self.listeners.iter().any(|name| name == listener)
+    }
+}
+
+/// 静态资源
+#[derive(Debug, Clone)]
+pub struct StaticAsset {
+    pub data: Bytes,
+    /// 不含引号的实体标签
+    pub etag: String,
+}
+
+/// 静态资源来源，可接入 `rust-embed` 等打包工具
+#[async_trait]
+pub trait StaticSource: Send + Sync + 'static {
+    /// 按相对路径读取资源，路径不以 `/` 开头且已排除 `..`
+    async fn get(&self, path: &str) -> Option<StaticAsset>;
+}
+
+/// 本地目录
+///
+/// 每次请求都会将整个文件读入内存，且不支持 `Range` 请求，适合前端构建产物等小文件。
+/// 大文件或需要断点续传的下载建议交给 Nginx 等反向代理，或使用 `tower_http::services::ServeDir`。
+#[derive(Debug, Clone)]
+pub struct StaticDir {
+    root: PathBuf,
+}
+
+impl StaticDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl StaticSource for StaticDir {
+    async fn get(&self, path: &str) -> Option<StaticAsset> {
+        let path = self.root.join(path);
+        let metadata = tokio::fs::metadata(&path).await.ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        let data = tokio::fs::read(&path).await.ok()?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|time| time.as_nanos())
+            .unwrap_or_default();
+        let etag = format!("{:x}-{:x}", data.len(), modified);
+        Some(StaticAsset { data: Bytes::from(data), etag })
+    }
+}
+
+/// 编译期嵌入的资源，例如 `[("index.html", include_bytes!("../web/dist/index.html"))]`
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddedAssets {
+    assets: HashMap<&'static str, StaticAsset>,
+}
+
+impl EmbeddedAssets {
+    pub fn new(assets: &[(&'static str, &'static [u8])]) -> Self {
+        let assets = assets
+            .iter()
+            .map(|(path, data)| {
+                let mut hasher = DefaultHasher::new();
+                data.hash(&mut hasher);
+                let etag = format!("{:x}-{:x}", data.len(), hasher.finish());
+                (path.trim_start_matches('/'), StaticAsset { data: Bytes::from_static(data), etag })
+            })
+            .collect();
+        Self { assets }
+    }
+}
+
+#[async_trait]
+impl StaticSource for EmbeddedAssets {
+    async fn get(&self, path: &str) -> Option<StaticAsset> {
+        self.assets.get(path).cloned()
+    }
+}
+
+#[derive(Clone)]
+struct StaticFiles {
+    source: Arc<dyn StaticSource>,
+    config: Arc<StaticFilesConfig>,
+}
+
+/// 静态资源路由，挂载到 `mount` 下
+pub(crate) fn router(source: Arc<dyn StaticSource>, config: &StaticFilesConfig) -> Router {
+    let state = StaticFiles { source, config: Arc::new(config.clone()) };
+    Router::new().fallback(serve).with_state(state)
+}
+
+async fn serve(State(state): State<StaticFiles>, req: Request) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return (StatusCode::METHOD_NOT_ALLOWED, [(ALLOW, "GET, HEAD")]).into_response();
+    }
+
+    // 先解码再校验，避免 `%2e%2e` 等编码后的路径段绕过检查
+    let Ok(path) = percent_encoding::percent_decode_str(req.uri().path()).decode_utf8() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(mut path) = sanitize_path(&path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if path.is_empty() || path.ends_with('/') {
+        path.push_str(&state.config.index);
+    }
+
+    let config = &state.config;
+    let accepts_html = header_contains(&req, ACCEPT.as_str(), "text/html");
+    let has_extension = path.rsplit('/').next().is_some_and(|name| name.contains('.'));
+    let (path, mut asset) = match state.source.get(&path).await {
+        Some(asset) => (path, Some(asset)),
+        None if config.spa_fallback && accepts_html && !has_extension => (config.index.clone(), None),
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+
+    let mut encoding = None;
+    if config.precompressed {
+        for (name, ext) in [("br", "br"), ("gzip", "gz")] {
+            if header_contains(&req, ACCEPT_ENCODING.as_str(), name)
+                && let Some(found) = state.source.get(&format!("{}.{}", path, ext)).await
+            {
+                encoding = Some(name);
+                asset = Some(found);
+                break;
+            }
+        }
+    }
+    let asset = match asset {
+        Some(asset) => asset,
+        // SPA 回退时才需要读取首页
+        None => match state.source.get(&path).await {
+            Some(asset) => asset,
+            None => return StatusCode::NOT_FOUND.into_response(),
+        },
+    };
+
+    let cache_control = match path.rsplit('/').next() == Some(config.index.as_str()) {
+        true => &config.index_cache_control,
+        false => &config.cache_control,
+    };
+    let mut builder = Response::builder().header(CONTENT_TYPE, content_type(&path));
+    if let Ok(value) = HeaderValue::from_str(cache_control) {
+        builder = builder.header(CACHE_CONTROL, value);
+    }
+    if config.precompressed {
+        builder = builder.header(VARY, ACCEPT_ENCODING.as_str());
+    }
+    if let Some(encoding) = encoding {
+        builder = builder.header(CONTENT_ENCODING, encoding);
+    }
+    if config.etag {
+        // 不同编码的内容不同，实体标签也需要区分
+        let etag = match encoding {
+            Some(encoding) => format!("\"{}-{}\"", asset.etag, encoding),
+            None => format!("\"{}\"", asset.etag),
+        };
+        if if_none_match(&req, &etag) {
+            return builder
+                .status(StatusCode::NOT_MODIFIED)
+                .header(ETAG, etag)
+                .body(Body::empty())
+                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+        builder = builder.header(ETAG, etag);
+    }
+
+    let body = match req.method() == Method::HEAD {
+        true => Body::empty(),
+        false => Body::from(asset.data),
+    };
+    builder
+        .body(body)
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+/// 转为相对路径，包含 `..` 等非普通路径段时返回 None
+fn sanitize_path(path: &str) -> Option<String> {
+    let path = path.trim_start_matches('/');
+    if path.contains('\\') {
+        return None;
+    }
+    let normal = PathBuf::from(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    normal.then(|| path.to_string())
+}
+
+fn header_contains(req: &Request, name: &str, value: &str) -> bool {
+    req.headers()
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|item| {
+            let mut parts = item.split(';');
+            let matched = parts.next().is_some_and(|v| v.trim().eq_ignore_ascii_case(value));
+            // 忽略 q=0 表示的显式拒绝
+            matched && !parts.any(|param| matches!(param.trim(), "q=0" | "q=0.0" | "q=0.00" | "q=0.000"))
+        })
+}
+
+fn if_none_match(req: &Request, etag: &str) -> bool {
+    req.headers()
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+fn content_type(path: &str) -> &'static str {
+    let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json" | "map") => "application/json",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("wasm") => "application/wasm",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::get(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn body(resp: Response) -> String {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_embedded_assets() {
+        let assets = EmbeddedAssets::new(&[
+            ("index.html", b"<html></html>"),
+            ("assets/app.js", b"console.log(1)"),
+            ("assets/app.js.br", b"br-content"),
+        ]);
+        let router = Router::new().nest_service("/admin", router(Arc::new(assets), &StaticFilesConfig::default()));
+
+        let resp = router.clone().oneshot(request("/admin/", &[])).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[CACHE_CONTROL], "no-cache");
+        assert_eq!(body(resp).await, "<html></html>");
+
+        // SPA 路由回退到首页，缺失的资源文件仍返回 404
+        let resp = router
+            .clone()
+            .oneshot(request("/admin/users/1", &[("accept", "text/html")]))
+            .await
+            .unwrap();
+        assert_eq!(body(resp).await, "<html></html>");
+        let resp = router
+            .clone()
+            .oneshot(request("/admin/assets/missing.js", &[("accept", "text/html")]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = router
+            .clone()
+            .oneshot(request("/admin/assets/app.js", &[("accept-encoding", "gzip, br")]))
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()[CONTENT_ENCODING], "br");
+        assert_eq!(resp.headers()[CONTENT_TYPE], "text/javascript; charset=utf-8");
+        assert_eq!(resp.headers()[CACHE_CONTROL], "public, max-age=3600");
+        let etag = resp.headers()[ETAG].clone();
+        assert_eq!(body(resp).await, "br-content");
+
+        let resp = router
+            .clone()
+            .oneshot(request(
+                "/admin/assets/app.js",
+                &[
+                    ("accept-encoding", "br"),
+                    ("if-none-match", etag.to_str().unwrap()),
+                ],
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let resp = router.oneshot(request("/admin/assets/app.js", &[])).await.unwrap();
+        assert!(resp.headers().get(CONTENT_ENCODING).is_none());
+        assert_ne!(resp.headers()[ETAG], etag);
+    }
+
+    #[tokio::test]
+    async fn test_static_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("index.html"), "index").unwrap();
+        std::fs::write(dir.path().join("style.css"), "body{}").unwrap();
+        std::fs::write(dir.path().join("my style.css"), "p{}").unwrap();
+        let router =
+            Router::new().nest_service("/web", router(Arc::new(StaticDir::new(dir.path())), &Default::default()));
+
+        let resp = router.clone().oneshot(request("/web/style.css", &[])).await.unwrap();
+        assert_eq!(resp.headers()[CONTENT_TYPE], "text/css; charset=utf-8");
+        assert_eq!(body(resp).await, "body{}");
+
+        let resp = router.clone().oneshot(request("/web/my%20style.css", &[])).await.unwrap();
+        assert_eq!(body(resp).await, "p{}");
+
+        for path in [
+            "/web/../secret",
+            "/web/%2e%2e/secret",
+            "/web/%2E%2E%2Fsecret",
+            "/web/a%5C..%5Csecret",
+        ] {
+            let resp = router.clone().oneshot(request(path, &[])).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", path);
+        }
+
+        let req = Request::post("/web/style.css").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,252 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use arc_swap::ArcSwap;
+use baizekit_app::anyhow::{Context, Result};
+use rustls::crypto::{CryptoProvider, ring};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::server::TlsStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+use crate::component::axum::TlsConfig;
+use crate::extract::ClientCertificate;
+
+/// 支持证书热更新的 TLS 配置
+pub(crate) struct ReloadableTlsConfig {
+    conf: TlsConfig,
+    http2: bool,
+    server_config: ArcSwap<ServerConfig>,
+    modified: Mutex<Vec<Option<SystemTime>>>,
+}
+
+impl ReloadableTlsConfig {
+    pub(crate) fn new(conf: TlsConfig, http2: bool) -> Result<Arc<Self>> {
+        let server_config = load_server_config(&conf, http2)?;
+        let modified = modified_times(&conf);
+
+        Ok(Arc::new(Self {
+            conf,
+            http2,
+            server_config: ArcSwap::from_pointee(server_config),
+            modified: Mutex::new(modified),
+        }))
+    }
+
+    pub(crate) fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.server_config.load_full())
+    }
+
+    /// 证书文件发生变化时重新加载，返回是否已重新加载
+    pub(crate) fn reload_if_changed(&self) -> Result<bool> {
+        let modified = modified_times(&self.conf);
+        let mut last_modified = self.modified.lock().expect("Failed to lock tls modified times");
+        if *last_modified == modified {
+            return Ok(false);
+        }
+
+        let server_config = load_server_config(&self.conf, self.http2)?;
+        self.server_config.store(Arc::new(server_config));
+        *last_modified = modified;
+        Ok(true)
+    }
+
+    /// 定期检查证书文件，直到 `shutdown` 被触发
+    pub(crate) fn spawn_watcher(self: Arc<Self>, shutdown: CancellationToken) {
+        if self.conf.reload_interval_seconds == 0 {
+            return;
+        }
+
+        let interval = Duration::from_secs(self.conf.reload_interval_seconds);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = shutdown.cancelled() => break,
+                }
+
+                match self.reload_if_changed() {
+                    Ok(true) => info!("TLS证书已重新加载: {}", self.conf.cert_path),
+                    Ok(false) => {}
+                    // 加载失败时继续使用旧证书
+                    Err(err) => error!("TLS证书重新加载失败: {:?}", err),
+                }
+            }
+        });
+    }
+}
+
+/// 从握手完成的连接中提取客户端证书
+pub(crate) fn client_certificate(stream: &TlsStream<TcpStream>) -> Option<ClientCertificate> {
+    let der = stream.get_ref().1.peer_certificates()?.first()?;
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    Some(ClientCertificate { subject: cert.subject().to_string(), der: der.to_vec() })
+}
+
+fn load_server_config(conf: &TlsConfig, http2: bool) -> Result<ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+    let certs = load_certs(&conf.cert_path)?;
+    let key = PrivateKeyDer::from_pem_file(&conf.key_path)
+        .with_context(|| format!("failed to load private key '{}'", conf.key_path))?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone()).with_safe_default_protocol_versions()?;
+    let builder = match &conf.client_ca_path {
+        None => builder.with_no_client_auth(),
+        Some(ca_path) => builder.with_client_cert_verifier(load_client_verifier(ca_path, conf, provider)?),
+    };
+
+    let mut server_config = builder
+        .with_single_cert(certs, key)
+        .context("invalid certificate or private key")?;
+    server_config.alpn_protocols =
+        if http2 { vec![b"h2".to_vec(), b"http/1.1".to_vec()] } else { vec![b"http/1.1".to_vec()] };
+    Ok(server_config)
+}
+
+fn load_client_verifier(
+    ca_path: &str,
+    conf: &TlsConfig,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(ca_path)? {
+        roots
+            .add(cert)
+            .with_context(|| format!("invalid client CA certificate '{}'", ca_path))?;
+    }
+
+    let builder = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+    let builder = if conf.client_auth_optional { builder.allow_unauthenticated() } else { builder };
+    Ok(builder.build()?)
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("failed to load certificates '{}'", path))?;
+
+    if certs.is_empty() {
+        baizekit_app::anyhow::bail!("no certificate found in '{}'", path);
+    }
+    Ok(certs)
+}
+
+fn modified_times(conf: &TlsConfig) -> Vec<Option<SystemTime>> {
+    let modified = |path: &str| Path::new(path).metadata().and_then(|m| m.modified()).ok();
+
+    [
+        Some(&conf.cert_path),
+        Some(&conf.key_path),
+        conf.client_ca_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| modified(path))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair};
+    use rustls::ClientConfig;
+    use rustls::pki_types::ServerName;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::TlsConnector;
+
+    use super::*;
+    use crate::component::axum::AxumComponentConfig;
+    use crate::component::server::serve;
+
+    fn issue(name: &str, usage: ExtendedKeyUsagePurpose, ca: &Certificate, ca_key: &KeyPair) -> (Certificate, KeyPair) {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec![name.to_string()]).unwrap();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.extended_key_usages = vec![usage];
+        (params.signed_by(&key, ca, ca_key).unwrap(), key)
+    }
+
+    async fn request(addr: std::net::SocketAddr, config: Arc<ClientConfig>) -> (String, Vec<u8>) {
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let mut stream = TlsConnector::from(config).connect(server_name, tcp).await.unwrap();
+        let server_cert = stream.get_ref().1.peer_certificates().unwrap()[0].to_vec();
+
+        stream
+            .write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        let _ = stream.read_to_string(&mut resp).await;
+        (resp, server_cert)
+    }
+
+    #[tokio::test]
+    async fn test_mtls_and_reload() {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params.distinguished_name.push(DnType::CommonName, "baize test ca");
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+        let (server_cert, server_key) = issue("localhost", ExtendedKeyUsagePurpose::ServerAuth, &ca, &ca_key);
+        let (client_cert, client_key) = issue("order-service", ExtendedKeyUsagePurpose::ClientAuth, &ca, &ca_key);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_string_lossy().to_string();
+        std::fs::write(path("ca.pem"), ca.pem()).unwrap();
+        std::fs::write(path("server.pem"), server_cert.pem()).unwrap();
+        std::fs::write(path("server.key"), server_key.serialize_pem()).unwrap();
+
+        let conf = TlsConfig {
+            cert_path: path("server.pem"),
+            key_path: path("server.key"),
+            client_ca_path: Some(path("ca.pem")),
+            client_auth_optional: false,
+            reload_interval_seconds: 0,
+        };
+        let tls = ReloadableTlsConfig::new(conf, true).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = crate::component::server::Listener::Tcp(listener);
+        let router = Router::new().route("/whoami", get(|cert: ClientCertificate| async move { cert.subject }));
+        let shutdown = CancellationToken::new();
+        tokio::spawn(serve(listener, router, AxumComponentConfig::default(), Some(tls.clone()), shutdown.clone()));
+
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.der().clone()).unwrap();
+        let client_key = PrivateKeyDer::from_pem_slice(client_key.serialize_pem().as_bytes()).unwrap();
+        let client_config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_client_auth_cert(vec![client_cert.der().clone()], client_key)
+            .unwrap();
+        let client_config = Arc::new(client_config);
+
+        let (resp, served_cert) = request(addr, client_config.clone()).await;
+        assert!(resp.starts_with("HTTP/1.1 200 OK"), "{}", resp);
+        assert!(resp.contains("CN=order-service"), "{}", resp);
+        assert_eq!(served_cert, server_cert.der().to_vec());
+
+        // 替换证书文件后重新加载
+        assert!(!tls.reload_if_changed().unwrap());
+        let (new_cert, new_key) = issue("localhost", ExtendedKeyUsagePurpose::ServerAuth, &ca, &ca_key);
+        std::fs::write(path("server.pem"), new_cert.pem()).unwrap();
+        std::fs::write(path("server.key"), new_key.serialize_pem()).unwrap();
+        assert!(tls.reload_if_changed().unwrap());
+
+        let (resp, served_cert) = request(addr, client_config).await;
+        assert!(resp.starts_with("HTTP/1.1 200 OK"), "{}", resp);
+        assert_eq!(served_cert, new_cert.der().to_vec());
+
+        shutdown.cancel();
+    }
+}
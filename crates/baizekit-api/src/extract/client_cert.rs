@@ -0,0 +1,43 @@
+use axum::body::Body;
+use axum::extract::{FromRequestParts, OptionalFromRequestParts};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::response::Response;
+
+/// mTLS 客户端证书，由 TLS 监听器在握手成功后注入请求扩展
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCertificate {
+    /// 证书主题，例如 `CN=order-service, O=baize`
+    pub subject: String,
+    /// 证书 DER 编码
+    pub der: Vec<u8>,
+}
+
+impl<S> FromRequestParts<S> for ClientCertificate
+where
+    S: Send + Sync,
+{
+    type Rejection = Response<Body>;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        if let Some(cert) = parts.extensions.get::<ClientCertificate>() {
+            return Ok(cert.clone());
+        }
+
+        Err(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::from("Unauthorized"))
+            .unwrap())
+    }
+}
+
+impl<S> OptionalFromRequestParts<S> for ClientCertificate
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<ClientCertificate>().cloned())
+    }
+}
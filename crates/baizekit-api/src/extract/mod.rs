@@ -1,3 +1,7 @@
+mod client_cert;
+mod page;
 mod principal;
 
+pub use client_cert::*;
+pub use page::*;
 pub use principal::*;
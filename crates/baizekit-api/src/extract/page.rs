@@ -0,0 +1,198 @@
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use baizekit_app::anyhow::bail;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::response::Reply;
+
+/// 分页参数配置，读取自 `axum.{label}.page`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PageQueryConfig {
+    /// 未传 `size` 时的默认每页大小，大于 `max_size` 时取 `max_size`
+    pub default_size: u64,
+    /// 每页大小上限，超过时截断为该值
+    pub max_size: u64,
+}
+
+impl Default for PageQueryConfig {
+    fn default() -> Self {
+        Self { default_size: 20, max_size: 100 }
+    }
+}
+
+impl PageQueryConfig {
+    pub fn validate(&self) -> baizekit_app::anyhow::Result<()> {
+        if self.default_size == 0 || self.max_size == 0 {
+            bail!("page default_size and max_size must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// 分页查询字符串，仅用于反序列化和 OpenAPI 文档，例如 `params(PageParams)`
+#[derive(Debug, Default, Deserialize, Serialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// 页码，从 1 开始
+    pub page: Option<u64>,
+    /// 每页大小
+    #[serde(alias = "page_size")]
+    pub size: Option<u64>,
+    /// 游标分页令牌，取自上一页的 `next_cursor`
+    pub cursor: Option<String>,
+}
+
+/// 分页查询提取器
+///
+/// 支持偏移分页 `?page=2&size=20` 与游标分页 `?cursor=xxx&size=20`，
+/// 默认值与上限取自请求扩展中的 [`PageQueryConfig`]，未配置时使用 [`PageQueryConfig::default`]。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageQuery {
+    /// 页码，从 1 开始
+    pub page: u64,
+    /// 每页大小
+    pub size: u64,
+    /// 游标分页令牌
+    pub cursor: Option<String>,
+}
+
+impl PageQuery {
+    pub fn from_params(params: PageParams, config: &PageQueryConfig) -> Self {
+        let size = match params.size {
+            None | Some(0) => config.default_size,
+            Some(size) => size,
+        }
+        .min(config.max_size);
+        let page = params.page.unwrap_or(1).max(1);
+        let cursor = params.cursor.filter(|c| !c.is_empty());
+
+        Self { page, size, cursor }
+    }
+
+    /// 偏移量，即 `(page - 1) * size`，溢出时取 `u64::MAX`
+    pub fn offset(&self) -> u64 {
+        (self.page - 1).saturating_mul(self.size)
+    }
+
+    pub fn is_cursor(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// 解码游标令牌，未携带或令牌无效时返回 `None`
+    pub fn decode_cursor<T: DeserializeOwned>(&self) -> Option<T> {
+        self.cursor.as_deref().and_then(decode_cursor)
+    }
+}
+
+impl<S> FromRequestParts<S> for PageQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = Response<Body>;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let config = parts.extensions.get::<PageQueryConfig>().copied().unwrap_or_default();
+
+        match Query::<PageParams>::try_from_uri(&parts.uri) {
+            Ok(Query(params)) => Ok(PageQuery::from_params(params, &config)),
+            Err(err) => {
+                let reply = Reply::<()> { code: 400, message: err.body_text(), data: None };
+                Err((StatusCode::BAD_REQUEST, Json(reply)).into_response())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "seaorm")]
+impl From<&PageQuery> for baizekit_seaorm::curd::Pagination {
+    fn from(query: &PageQuery) -> Self {
+        match query.cursor {
+            Some(_) => Self::Cursor(query.size),
+            None => Self::Offset(query.page, query.size),
+        }
+    }
+}
+
+#[cfg(feature = "seaorm")]
+impl From<PageQuery> for baizekit_seaorm::curd::Pagination {
+    fn from(query: PageQuery) -> Self {
+        Self::from(&query)
+    }
+}
+
+/// 将游标值编码为不透明令牌(JSON + URL safe base64)
+pub fn encode_cursor<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_vec(value).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+/// 解码 [`encode_cursor`] 生成的令牌
+pub fn decode_cursor<T: DeserializeOwned>(token: &str) -> Option<T> {
+    let json = URL_SAFE_NO_PAD.decode(token).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+
+    use super::*;
+
+    async fn extract(uri: &str, config: Option<PageQueryConfig>) -> Result<PageQuery, Response<Body>> {
+        let (mut parts, _) = Request::builder().uri(uri).body(()).unwrap().into_parts();
+        if let Some(config) = config {
+            parts.extensions.insert(config);
+        }
+        PageQuery::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_defaults_and_max_size() {
+        let query = extract("/users", None).await.unwrap();
+        assert_eq!(query, PageQuery { page: 1, size: 20, cursor: None });
+
+        let query = extract("/users?page=3&size=1000", None).await.unwrap();
+        assert_eq!((query.page, query.size, query.offset()), (3, 100, 200));
+
+        let config = PageQueryConfig { default_size: 5, max_size: 10 };
+        let query = extract("/users?page=0&page_size=0", Some(config)).await.unwrap();
+        assert_eq!((query.page, query.size), (1, 5));
+
+        let query = extract(&format!("/users?page={}&size=100", u64::MAX), None).await.unwrap();
+        assert_eq!(query.offset(), u64::MAX);
+
+        let config = PageQueryConfig { default_size: 50, max_size: 10 };
+        let query = extract("/users", Some(config)).await.unwrap();
+        assert_eq!(query.size, 10);
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(PageQueryConfig::default().validate().is_ok());
+        assert!(PageQueryConfig { default_size: 0, max_size: 10 }.validate().is_err());
+        assert!(PageQueryConfig { default_size: 10, max_size: 0 }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query() {
+        let resp = extract("/users?page=abc", None).await.unwrap_err();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_roundtrip() {
+        let token = encode_cursor(&(42i64, "2025-01-01"));
+        let query = extract(&format!("/users?cursor={}&size=10", token), None).await.unwrap();
+        assert!(query.is_cursor());
+        assert_eq!(query.decode_cursor::<(i64, String)>(), Some((42, "2025-01-01".to_string())));
+        assert_eq!(decode_cursor::<i64>("not-a-token"), None);
+    }
+}
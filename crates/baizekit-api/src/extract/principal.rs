@@ -1,19 +1,24 @@
+use std::convert::Infallible;
 use std::future::Future;
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use axum::body::Body;
 use axum::extract::FromRequestParts;
 use axum::http::header::HeaderName;
 use axum::http::request::Parts;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
 use axum::response::Response;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 
 pub const SYSTEM_TENANT_ID: &str = "SYSTEM_TENANT_ID";
 pub const CUSTOM_ADMIN_PRINCIPAL_HEADER: &str = "x-admin-principal";
-static CUSTOM_ADMIN_PRINCIPAL_HEADER_NAME: LazyLock<HeaderName> =
-    LazyLock::new(|| HeaderName::from_str(CUSTOM_ADMIN_PRINCIPAL_HEADER).unwrap());
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AdminPrincipal {
@@ -44,23 +49,14 @@ where
 
     fn from_request_parts(parts: &mut Parts, _: &S) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
         async {
-            // 提取自定义的头条目
-            if let Some(auth_header) = parts.headers.get(&*CUSTOM_ADMIN_PRINCIPAL_HEADER_NAME) {
-                if let Ok(auth_str) = auth_header.to_str() {
-                    // 解析JSON字符串成Principal对象
-                    if let Ok(principal) = serde_json::from_str::<AdminPrincipal>(auth_str) {
-                        tracing::info!("Admin principal: {:?}", principal);
-                        return Ok(principal);
-                    }
-                }
+            if let Some(principal) = extract_principal::<AdminPrincipal>(parts) {
+                tracing::info!("Admin principal: {:?}", principal);
+                return Ok(principal);
             }
 
             tracing::info!("Admin principal not found in headers");
             // 如果提取或解析失败，返回 401 Unauthorized 响应
-            Err(Response::builder()
-                .status(StatusCode::UNAUTHORIZED)
-                .body(Body::from("Unauthorized"))
-                .unwrap())
+            Err(unauthorized())
         }
     }
 }
@@ -80,19 +76,282 @@ where
     type Rejection = Response<Body>;
 
     async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
-        let header_name = HeaderName::from_str(CUSTOM_PRINCIPAL_HEADER).unwrap();
+        extract_principal::<EndUserPrincipal>(parts).ok_or_else(unauthorized)
+    }
+}
+
+/// 通过请求头透传的身份
+pub trait Principal: Serialize + DeserializeOwned {
+    /// 身份所在的请求头，签名位于 `{HEADER}-signature`
+    const HEADER: &'static str;
+}
+
+impl Principal for AdminPrincipal {
+    const HEADER: &'static str = CUSTOM_ADMIN_PRINCIPAL_HEADER;
+}
+
+impl Principal for EndUserPrincipal {
+    const HEADER: &'static str = CUSTOM_PRINCIPAL_HEADER;
+}
+
+/// 身份请求头的签名后缀，例如 `x-principal-signature`
+pub const PRINCIPAL_SIGNATURE_SUFFIX: &str = "-signature";
+
+/// 身份请求头签名配置，读取自 `axum.{label}.principal_signing`
+///
+/// `secret` 非空时，身份提取器只接受带有效签名的请求头，防止内部调用方伪造身份。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PrincipalSigningConfig {
+    /// 服务间共享的 HMAC-SHA256 密钥，为空时不校验签名
+    pub secret: String,
+    /// 签名时间戳与当前时间允许的最大偏差(秒)，超出视为重放
+    pub max_skew_seconds: u64,
+}
+
+impl Default for PrincipalSigningConfig {
+    fn default() -> Self {
+        Self { secret: String::new(), max_skew_seconds: 300 }
+    }
+}
+
+/// 身份请求头的签名与校验
+///
+/// 签名格式为 `t={unix 秒},s={base64url(HMAC-SHA256(t \n header \n value))}`，
+/// 签名中包含请求头名称，终端用户的签名不能用于管理员身份。
+#[derive(Clone)]
+pub struct PrincipalSigner {
+    key: Arc<[u8]>,
+    max_skew: u64,
+}
+
+impl PrincipalSigner {
+    /// 未配置密钥时返回 None
+    pub fn new(config: &PrincipalSigningConfig) -> Option<Self> {
+        if config.secret.is_empty() {
+            return None;
+        }
+        Some(Self::from_secret(&config.secret).with_max_skew(Duration::from_secs(config.max_skew_seconds)))
+    }
+
+    pub fn from_secret(secret: impl AsRef<[u8]>) -> Self {
+        Self { key: Arc::from(secret.as_ref()), max_skew: 300 }
+    }
+
+    pub fn with_max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew.as_secs();
+        self
+    }
+
+    /// 使用当前时间对请求头的值签名
+    pub fn sign(&self, header: &str, value: &str) -> String {
+        self.sign_at(header, value, unix_now())
+    }
+
+    fn sign_at(&self, header: &str, value: &str, timestamp: u64) -> String {
+        format!(
+            "t={},s={}",
+            timestamp,
+            URL_SAFE_NO_PAD.encode(self.mac(header, value, timestamp).finalize().into_bytes())
+        )
+    }
+
+    fn mac(&self, header: &str, value: &str, timestamp: u64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any size");
+        mac.update(format!("{}\n{}\n{}", timestamp, header, value).as_bytes());
+        mac
+    }
+
+    /// 校验签名及时间戳
+    pub fn verify(&self, header: &str, value: &str, signature: &str) -> bool {
+        let Some((timestamp, sig)) = parse_signature(signature) else {
+            return false;
+        };
+        if unix_now().abs_diff(timestamp) > self.max_skew {
+            return false;
+        }
+        let Ok(sig) = URL_SAFE_NO_PAD.decode(sig) else {
+            return false;
+        };
+        self.mac(header, value, timestamp).verify_slice(&sig).is_ok()
+    }
+
+    /// 生成携带签名身份的请求头，用于调用下游服务
+    pub fn headers<P: Principal>(&self, principal: &P) -> HeaderMap {
+        let value = serde_json::to_string(principal).expect("failed to serialize principal");
+        let mut headers = HeaderMap::new();
+        self.insert(&mut headers, P::HEADER, &value);
+        headers
+    }
+
+    fn insert(&self, headers: &mut HeaderMap, header: &'static str, value: &str) {
+        let signature = self.sign(header, value);
+        if let (Ok(value), Ok(signature)) = (HeaderValue::from_str(value), HeaderValue::from_str(&signature)) {
+            headers.insert(header, value);
+            headers.insert(signature_header(header), signature);
+        }
+    }
+}
 
-        if let Some(auth_header) = parts.headers.get(&header_name) {
-            if let Ok(auth_str) = auth_header.to_str() {
-                if let Ok(principal) = serde_json::from_str::<EndUserPrincipal>(auth_str) {
-                    return Ok(principal);
+fn parse_signature(signature: &str) -> Option<(u64, &str)> {
+    let (timestamp, sig) = signature.split_once(',')?;
+    let timestamp = timestamp.strip_prefix("t=")?.parse().ok()?;
+    Some((timestamp, sig.strip_prefix("s=")?))
+}
+
+pub(crate) fn signature_header(header: &str) -> HeaderName {
+    HeaderName::from_str(&format!("{}{}", header, PRINCIPAL_SIGNATURE_SUFFIX)).unwrap()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 读取请求头中的身份；传入签名器时签名缺失或无效视为不存在
+fn principal_value<'a>(headers: &'a HeaderMap, header: &str, signer: Option<&PrincipalSigner>) -> Option<&'a str> {
+    let value = headers.get(header)?.to_str().ok()?;
+    if let Some(signer) = signer {
+        let signature = headers.get(signature_header(header)).and_then(|v| v.to_str().ok());
+        if !signature.is_some_and(|signature| signer.verify(header, value, signature)) {
+            tracing::warn!(header, "principal signature missing or invalid");
+            return None;
+        }
+    }
+    Some(value)
+}
+
+fn parse_principal<P: Principal>(headers: &HeaderMap, signer: Option<&PrincipalSigner>) -> Option<P> {
+    serde_json::from_str(principal_value(headers, P::HEADER, signer)?).ok()
+}
+
+fn extract_principal<P: Principal>(parts: &Parts) -> Option<P> {
+    parse_principal(&parts.headers, parts.extensions.get::<PrincipalSigner>())
+}
+
+/// 中间件使用的请求身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestPrincipal {
+    /// `admin:{admin_id}` 或 `user:{id}`
+    pub id: String,
+    pub account: String,
+    pub tenant_id: String,
+}
+
+/// 识别请求身份，管理员优先；传入签名器时签名缺失或无效视为匿名
+pub(crate) fn request_principal(headers: &HeaderMap, signer: Option<&PrincipalSigner>) -> Option<RequestPrincipal> {
+    if let Some(p) = parse_principal::<AdminPrincipal>(headers, signer) {
+        return Some(RequestPrincipal {
+            id: format!("admin:{}", p.admin_id),
+            account: p.account,
+            tenant_id: p.tenant_id,
+        });
+    }
+    parse_principal::<EndUserPrincipal>(headers, signer).map(|p| RequestPrincipal {
+        id: format!("user:{}", p.id),
+        account: p.account,
+        tenant_id: p.tenant_id,
+    })
+}
+
+fn unauthorized() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .body(Body::from("Unauthorized"))
+        .unwrap()
+}
+
+/// 当前请求的身份请求头，用于透传给下游服务
+///
+/// 启用签名时使用当前时间重新签名，避免下游因时间戳过期拒绝；未通过校验的身份不会被透传。
+///
+/// ```ignore
+/// async fn handler(ForwardedPrincipal(headers): ForwardedPrincipal) {
+///     client.request(Method::GET, "/api/orders").headers(headers).send().await;
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ForwardedPrincipal(pub HeaderMap);
+
+impl<S> FromRequestParts<S> for ForwardedPrincipal
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let signer = parts.extensions.get::<PrincipalSigner>();
+        let mut headers = HeaderMap::new();
+        for header in [CUSTOM_ADMIN_PRINCIPAL_HEADER, CUSTOM_PRINCIPAL_HEADER] {
+            let Some(value) = principal_value(&parts.headers, header, signer) else {
+                continue;
+            };
+            match signer {
+                Some(signer) => signer.insert(&mut headers, header, value),
+                None => {
+                    if let Ok(value) = HeaderValue::from_str(value) {
+                        headers.insert(header, value);
+                    }
                 }
             }
         }
+        Ok(Self(headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signed_principal() {
+        let signer = PrincipalSigner::from_secret("s3cret");
+        let router = Router::new()
+            .route("/me", get(|principal: EndUserPrincipal| async move { principal.account }))
+            .route(
+                "/forward",
+                get(|ForwardedPrincipal(headers): ForwardedPrincipal| async move { headers.len().to_string() }),
+            )
+            .layer(Extension(signer.clone()));
+        let send = |headers: HeaderMap| {
+            let mut req = axum::http::Request::get("/me").body(Body::empty()).unwrap();
+            *req.headers_mut() = headers;
+            router.clone().oneshot(req)
+        };
+
+        let principal = EndUserPrincipal { id: 1, account: "alice".to_string(), tenant_id: "t1".to_string() };
+        let headers = signer.headers(&principal);
+        assert_eq!(send(headers.clone()).await.unwrap().status(), StatusCode::OK);
+
+        // 未签名、篡改、签名用于其他请求头、时间戳过期均拒绝
+        let mut unsigned = headers.clone();
+        unsigned.remove("x-principal-signature");
+        assert_eq!(send(unsigned).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let mut forged = headers.clone();
+        forged
+            .insert(CUSTOM_PRINCIPAL_HEADER, HeaderValue::from_static(r#"{"id":2,"account":"bob","tenant_id":"t1"}"#));
+        assert_eq!(send(forged).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let value = headers[CUSTOM_PRINCIPAL_HEADER].to_str().unwrap();
+        assert!(!signer.verify(
+            CUSTOM_ADMIN_PRINCIPAL_HEADER,
+            value,
+            headers["x-principal-signature"].to_str().unwrap()
+        ));
+        let expired = signer.sign_at(CUSTOM_PRINCIPAL_HEADER, value, unix_now() - 600);
+        assert!(!signer.verify(CUSTOM_PRINCIPAL_HEADER, value, &expired));
 
-        Err(Response::builder()
-            .status(StatusCode::UNAUTHORIZED)
-            .body(Body::from("Unauthorized"))
-            .unwrap())
+        let mut req = axum::http::Request::get("/forward").body(Body::empty()).unwrap();
+        *req.headers_mut() = headers;
+        let resp = router.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"2");
     }
 }
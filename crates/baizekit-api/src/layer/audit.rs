@@ -0,0 +1,438 @@
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use axum::body::{Body, Bytes, HttpBody};
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::HeaderMap;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::Response;
+use baizekit_app::anyhow::Result;
+use baizekit_app::async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::extract::{PrincipalSigner, request_principal};
+use crate::layer::rate_limit::path_has_prefix;
+
+/// 审计日志配置，读取自 `axum.{label}.audit`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// 记录的请求方法，为空时记录所有方法
+    pub methods: Vec<String>,
+    /// 不记录的路径前缀，按路径段匹配，例如健康检查和指标端点
+    pub exclude_paths: Vec<String>,
+    /// 是否记录 JSON 请求体
+    pub request_body: bool,
+    /// 是否记录 JSON 响应体
+    pub response_body: bool,
+    /// 可记录的请求体或响应体大小上限(字节)，超出或长度未知的流式请求体、响应体不记录
+    pub max_body_size: usize,
+    /// 需脱敏的字段路径，例如 `$.user.id_card`、`$.items.*.token`、`$..password`
+    ///
+    /// `*` 匹配任意字段或数组元素，`..` 匹配任意层级，`$.` 前缀可省略。
+    pub redact: Vec<String>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            methods: Vec::new(),
+            exclude_paths: vec!["/health".to_string(), "/metrics".to_string()],
+            request_body: false,
+            response_body: false,
+            max_body_size: 16 * 1024,
+            redact: vec!["$..password".to_string()],
+        }
+    }
+}
+
+/// 脱敏后的占位值
+pub const REDACTED: &str = "***";
+
+/// 审计记录
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AuditRecord {
+    /// 请求时间(毫秒时间戳)
+    pub timestamp: u64,
+    /// 取自 `x-request-id` 请求头
+    pub request_id: Option<String>,
+    /// 调用主体，例如 `admin:1`、`user:2`
+    pub principal: Option<String>,
+    pub account: Option<String>,
+    pub tenant_id: Option<String>,
+    pub method: String,
+    /// 路由模板，例如 `/users/{id}`
+    pub route: Option<String>,
+    pub path: String,
+    pub status: u16,
+    /// 从收到请求到返回响应头的耗时(毫秒)，不包含流式响应体的传输时间
+    pub latency_ms: u64,
+    pub request_body: Option<Value>,
+    pub response_body: Option<Value>,
+}
+
+/// 审计记录输出
+#[async_trait]
+pub trait AuditSink: Send + Sync + 'static {
+    async fn record(&self, record: &AuditRecord) -> Result<()>;
+}
+
+/// 输出到 `audit` target 的 tracing 日志
+#[derive(Debug, Default)]
+pub struct TracingAuditSink;
+
+#[async_trait]
+impl AuditSink for TracingAuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        tracing::info!(target: "audit", "{}", serde_json::to_string(record)?);
+        Ok(())
+    }
+}
+
+/// 发送到 Kafka 主题，以租户 ID 作为消息键
+#[cfg(feature = "kafka")]
+pub struct KafkaAuditSink {
+    producer: baizekit_kafka::sync_producer::SyncProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaAuditSink {
+    pub fn new(producer: baizekit_kafka::sync_producer::SyncProducer, topic: impl Into<String>) -> Self {
+        Self { producer, topic: topic.into() }
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl AuditSink for KafkaAuditSink {
+    async fn record(&self, record: &AuditRecord) -> Result<()> {
+        let payload = serde_json::to_string(record)?;
+        let key = record.tenant_id.clone().unwrap_or_default();
+        let producer = self.producer.clone();
+        let topic = self.topic.clone();
+        // 发送时会阻塞等待 flush
+        tokio::task::spawn_blocking(move || producer.send_with_key(topic, key, payload)).await??;
+        Ok(())
+    }
+}
+
+/// 审计中间件状态
+#[derive(Clone)]
+pub struct Audit {
+    config: Arc<AuditConfig>,
+    sink: Arc<dyn AuditSink>,
+    redact: Arc<Vec<Vec<String>>>,
+    signer: Option<PrincipalSigner>,
+}
+
+impl Audit {
+    pub fn new(config: AuditConfig, sink: Arc<dyn AuditSink>) -> Self {
+        let redact = config.redact.iter().map(|path| parse_redact_path(path)).collect();
+        Self { config: Arc::new(config), sink, redact: Arc::new(redact), signer: None }
+    }
+
+    /// 启用身份签名时，签名无效的身份按匿名记录
+    pub fn with_signer(mut self, signer: Option<PrincipalSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    fn applies_to(&self, req: &Request) -> bool {
+        let path = req.uri().path();
+        let method = req.method().as_str();
+        !self.config.exclude_paths.iter().any(|prefix| path_has_prefix(path, prefix))
+            && (self.config.methods.is_empty() || self.config.methods.iter().any(|m| m.eq_ignore_ascii_case(method)))
+    }
+
+    /// 长度已知且不超过上限的 JSON 请求体或响应体才读取，避免缓冲流式数据
+    fn capturable(&self, headers: &HeaderMap, body: &Body) -> bool {
+        let is_json = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("json"));
+        is_json
+            && body
+                .size_hint()
+                .upper()
+                .is_some_and(|size| size <= self.config.max_body_size as u64)
+    }
+
+    fn redacted(&self, bytes: &Bytes) -> Option<Value> {
+        let mut value = serde_json::from_slice(bytes).ok()?;
+        for path in self.redact.iter() {
+            redact(&mut value, path);
+        }
+        Some(value)
+    }
+}
+
+/// 解析脱敏路径，`..` 解析为空字符串
+fn parse_redact_path(path: &str) -> Vec<String> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+    path.split('.').map(str::to_string).collect()
+}
+
+/// 将路径匹配的字段替换为 [`REDACTED`]
+fn redact(value: &mut Value, path: &[String]) {
+    let Some((head, rest)) = path.split_first() else {
+        *value = Value::from(REDACTED);
+        return;
+    };
+
+    // 任意层级：在当前节点和所有子节点上匹配剩余路径
+    if head.is_empty() {
+        redact(value, rest);
+        match value {
+            Value::Object(map) => map.values_mut().for_each(|v| redact(v, path)),
+            Value::Array(items) => items.iter_mut().for_each(|v| redact(v, path)),
+            _ => {}
+        }
+        return;
+    }
+
+    match value {
+        Value::Object(map) if head == "*" => map.values_mut().for_each(|v| redact(v, rest)),
+        Value::Object(map) => {
+            if let Some(v) = map.get_mut(head) {
+                redact(v, rest);
+            }
+        }
+        // 数组中的对象按同一路径处理，例如 `items.token` 等价于 `items.*.token`
+        Value::Array(items) if head == "*" => items.iter_mut().for_each(|v| redact(v, rest)),
+        Value::Array(items) => items.iter_mut().for_each(|v| redact(v, path)),
+        _ => {}
+    }
+}
+
+/// 审计中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 记录异步写入 [`AuditSink`]，不阻塞响应；写入失败只输出错误日志。
+pub async fn audit(State(audit): State<Audit>, req: Request, next: Next) -> Response {
+    if !audit.applies_to(&req) {
+        return next.run(req).await;
+    }
+
+    let start = Instant::now();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let (principal, account, tenant_id) = match request_principal(req.headers(), audit.signer.as_ref()) {
+        Some(p) => (Some(p.id), Some(p.account), Some(p.tenant_id)),
+        None => (None, None, None),
+    };
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let route = req.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string());
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+
+    let (req, request_body) = match audit.config.request_body && audit.capturable(req.headers(), req.body()) {
+        true => {
+            let (parts, body) = req.into_parts();
+            match axum::body::to_bytes(body, audit.config.max_body_size).await {
+                Ok(bytes) => {
+                    let value = audit.redacted(&bytes);
+                    (Request::from_parts(parts, Body::from(bytes)), value)
+                }
+                // 请求体已被消费，无法继续处理
+                Err(err) => {
+                    tracing::warn!("read request body failed: {}", err);
+                    (Request::from_parts(parts, Body::empty()), None)
+                }
+            }
+        }
+        false => (req, None),
+    };
+
+    let resp = next.run(req).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let status = resp.status().as_u16();
+
+    let (resp, response_body) = match audit.config.response_body && audit.capturable(resp.headers(), resp.body()) {
+        true => {
+            let (parts, body) = resp.into_parts();
+            match axum::body::to_bytes(body, audit.config.max_body_size).await {
+                Ok(bytes) => {
+                    let value = audit.redacted(&bytes);
+                    (Response::from_parts(parts, Body::from(bytes)), value)
+                }
+                Err(err) => {
+                    tracing::warn!("read response body failed: {}", err);
+                    (Response::from_parts(parts, Body::empty()), None)
+                }
+            }
+        }
+        false => (resp, None),
+    };
+
+    let record = AuditRecord {
+        timestamp,
+        request_id,
+        principal,
+        account,
+        tenant_id,
+        method,
+        route,
+        path,
+        status,
+        latency_ms,
+        request_body,
+        response_body,
+    };
+    let sink = audit.sink.clone();
+    tokio::spawn(async move {
+        if let Err(err) = sink.record(&record).await {
+            tracing::error!("write audit record failed: {:?}", err);
+        }
+    });
+
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use futures_util::stream;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::extract::CUSTOM_ADMIN_PRINCIPAL_HEADER;
+
+    #[derive(Default)]
+    struct MemorySink(Mutex<Vec<AuditRecord>>);
+
+    #[async_trait]
+    impl AuditSink for MemorySink {
+        async fn record(&self, record: &AuditRecord) -> Result<()> {
+            self.0.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut value = json!({
+            "password": "p",
+            "user": { "id_card": "123", "name": "a" },
+            "items": [{ "token": "t1" }, { "token": "t2", "nested": { "secret": "s" } }],
+        });
+        for path in ["$.password", "user.id_card", "items.token", "$..secret"] {
+            redact(&mut value, &parse_redact_path(path));
+        }
+        assert_eq!(
+            value,
+            json!({
+                "password": REDACTED,
+                "user": { "id_card": REDACTED, "name": "a" },
+                "items": [{ "token": REDACTED }, { "token": REDACTED, "nested": { "secret": REDACTED } }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_exclude_paths() {
+        let audit = Audit::new(AuditConfig::default(), Arc::new(MemorySink::default()));
+        let applies = |uri: &str| audit.applies_to(&Request::get(uri).body(Body::empty()).unwrap());
+        assert!(!applies("/health"));
+        assert!(!applies("/health/live"));
+        assert!(applies("/healthcare/patients"));
+        assert!(applies("/metricsx"));
+    }
+
+    #[tokio::test]
+    async fn test_audit() {
+        let sink = Arc::new(MemorySink::default());
+        let config = AuditConfig {
+            request_body: true,
+            response_body: true,
+            redact: vec!["$..password".to_string(), "$.secret".to_string()],
+            ..Default::default()
+        };
+        let router = Router::new()
+            .route(
+                "/users/{id}",
+                post(|Json(body): Json<Value>| async move { Json(json!({ "secret": "s", "echo": body })) }),
+            )
+            .route(
+                "/stream",
+                post(|| async {
+                    let chunks = stream::iter([Ok::<_, std::io::Error>("{\"a\":"), Ok("1}")]);
+                    ([(CONTENT_TYPE, "application/json")], Body::from_stream(chunks))
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(Audit::new(config, sink.clone()), audit));
+
+        let principal = r#"{"admin_id":1,"account":"root","tenant_id":"t1","tenant_owner":null}"#;
+        let req = Request::post("/users/7")
+            .header(CONTENT_TYPE, "application/json")
+            .header(CUSTOM_ADMIN_PRINCIPAL_HEADER, principal)
+            .header("x-request-id", "req-1")
+            .body(Body::from(r#"{"name":"a","password":"p"}"#))
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        // 脱敏只影响审计记录，不影响实际响应
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap()["echo"]["password"], "p");
+
+        let resp = router
+            .oneshot(Request::post("/stream").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"{\"a\":1}");
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let records = sink.0.lock().unwrap();
+        assert_eq!(records.len(), 2);
+
+        let record = &records[0];
+        assert_eq!(record.principal.as_deref(), Some("admin:1"));
+        assert_eq!(record.tenant_id.as_deref(), Some("t1"));
+        assert_eq!(record.request_id.as_deref(), Some("req-1"));
+        assert_eq!(record.route.as_deref(), Some("/users/{id}"));
+        assert_eq!(record.status, 200);
+        assert_eq!(record.request_body, Some(json!({ "name": "a", "password": REDACTED })));
+        assert_eq!(
+            record.response_body,
+            Some(json!({ "secret": REDACTED, "echo": { "name": "a", "password": REDACTED } }))
+        );
+
+        // 流式响应体不记录
+        assert_eq!(records[1].path, "/stream");
+        assert_eq!(records[1].response_body, None);
+    }
+
+    #[tokio::test]
+    async fn test_forged_principal() {
+        let sink = Arc::new(MemorySink::default());
+        let state =
+            Audit::new(AuditConfig::default(), sink.clone()).with_signer(Some(PrincipalSigner::from_secret("s3cret")));
+        let router = Router::new()
+            .route("/users", post(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(state, audit));
+
+        let principal = r#"{"admin_id":1,"account":"root","tenant_id":"t1","tenant_owner":null}"#;
+        let req = Request::post("/users")
+            .header(CUSTOM_ADMIN_PRINCIPAL_HEADER, principal)
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(req).await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let records = sink.0.lock().unwrap();
+        assert_eq!(records[0].principal, None);
+        assert_eq!(records[0].tenant_id, None);
+    }
+}
@@ -0,0 +1,658 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::header::{
+    ACCEPT, CACHE_CONTROL, CONNECTION, CONTENT_LENGTH, ETAG, IF_NONE_MATCH, SET_COOKIE, TRANSFER_ENCODING,
+};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use baizekit_app::anyhow::{Result, bail};
+use baizekit_app::async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::extract::{
+    CUSTOM_ADMIN_PRINCIPAL_HEADER, CUSTOM_PRINCIPAL_HEADER, PrincipalSigner, request_principal, signature_header,
+};
+use crate::layer::StoredResponse;
+use crate::layer::rate_limit::path_has_prefix;
+use crate::response::Reply;
+
+/// 缓存维度
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheVary {
+    /// 所有请求共享，仅用于与身份无关的响应
+    Public,
+    /// 按租户，未登录的请求共享
+    Tenant,
+    /// 按登录主体，未登录的请求共享
+    #[default]
+    Principal,
+}
+
+/// 按路由前缀配置的缓存规则
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CacheRoute {
+    /// 路由前缀，例如 `/api/v1/products`
+    pub prefix: String,
+    /// 缓存维度，默认按登录主体
+    #[serde(default)]
+    pub vary: CacheVary,
+    /// 缓存时间(秒)，同时作为 `Cache-Control` 的 `max-age`，必须大于 0
+    pub ttl_seconds: u64,
+    /// 缓存条目的标签，处理函数可通过 [`CacheTags`] 追加
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 响应缓存配置，读取自 `axum.{label}.cache`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// 按路由前缀配置的规则，最长前缀优先，未匹配的请求不缓存
+    pub routes: Vec<CacheRoute>,
+    /// 可缓存的响应体大小上限(字节)，超出或流式响应不缓存
+    pub max_body_size: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { routes: Vec::new(), max_body_size: 1 << 20 }
+    }
+}
+
+impl CacheConfig {
+    fn match_route(&self, path: &str) -> Option<&CacheRoute> {
+        self.routes
+            .iter()
+            .filter(|r| path_has_prefix(path, &r.prefix))
+            .max_by_key(|r| r.prefix.len())
+    }
+}
+
+/// 处理函数追加的缓存标签，作为响应的一部分返回
+///
+/// ```ignore
+/// async fn get_product(Path(id): Path<i64>) -> impl IntoResponse {
+///     (CacheTags::new([format!("product:{}", id)]), ApiOK::with_data(product))
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CacheTags(pub Vec<String>);
+
+impl CacheTags {
+    pub fn new<I: IntoIterator<Item = T>, T: Into<String>>(tags: I) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoResponseParts for CacheTags {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(self);
+        Ok(res)
+    }
+}
+
+/// 处理成功(2xx)后失效的缓存标签，通常由写接口返回
+///
+/// ```ignore
+/// async fn update_product(Path(id): Path<i64>) -> impl IntoResponse {
+///     (InvalidateCache::new([format!("product:{}", id), "products".to_string()]), ApiOK::ok())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InvalidateCache(pub Vec<String>);
+
+impl InvalidateCache {
+    pub fn new<I: IntoIterator<Item = T>, T: Into<String>>(tags: I) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoResponseParts for InvalidateCache {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(self);
+        Ok(res)
+    }
+}
+
+/// 响应缓存存储
+#[async_trait]
+pub trait ResponseCacheStore: Send + Sync + 'static {
+    async fn get(&self, key: &str) -> Result<Option<StoredResponse>>;
+
+    async fn put(&self, key: &str, resp: &StoredResponse, tags: &[String], ttl: Duration) -> Result<()>;
+
+    /// 删除带有任一标签的缓存条目
+    async fn invalidate(&self, tags: &[String]) -> Result<()>;
+}
+
+struct CacheEntry {
+    resp: StoredResponse,
+    tags: Vec<String>,
+    expires_at: Instant,
+}
+
+struct MemoryCache {
+    entries: lru::LruCache<String, CacheEntry>,
+    tags: HashMap<String, HashSet<String>>,
+}
+
+impl MemoryCache {
+    fn untag(&mut self, key: &str, entry: CacheEntry) {
+        for tag in entry.tags {
+            if let Some(keys) = self.tags.get_mut(&tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.pop(key) {
+            self.untag(key, entry);
+        }
+    }
+}
+
+/// 进程内 LRU 缓存，仅适用于单实例部署，多实例下失效无法同步
+pub struct MemoryResponseCacheStore {
+    cache: Mutex<MemoryCache>,
+}
+
+impl MemoryResponseCacheStore {
+    /// [`AxumComponentBuilder::with_response_cache`](crate::component::axum::AxumComponentBuilder::with_response_cache) 默认的条目数量上限
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    /// `capacity` 为缓存条目数量上限，超出时淘汰最久未访问的条目
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        let cache = MemoryCache { entries: lru::LruCache::new(capacity), tags: HashMap::new() };
+        Self { cache: Mutex::new(cache) }
+    }
+}
+
+#[async_trait]
+impl ResponseCacheStore for MemoryResponseCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<StoredResponse>> {
+        let mut cache = self.cache.lock().expect("Failed to lock response cache");
+        match cache.entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Ok(Some(entry.resp.clone())),
+            Some(_) => {
+                cache.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put(&self, key: &str, resp: &StoredResponse, tags: &[String], ttl: Duration) -> Result<()> {
+        let mut cache = self.cache.lock().expect("Failed to lock response cache");
+        cache.remove(key);
+        for tag in tags {
+            cache.tags.entry(tag.clone()).or_default().insert(key.to_string());
+        }
+        let entry = CacheEntry { resp: resp.clone(), tags: tags.to_vec(), expires_at: Instant::now() + ttl };
+        if let Some((evicted, entry)) = cache.entries.push(key.to_string(), entry) {
+            cache.untag(&evicted, entry);
+        }
+        Ok(())
+    }
+
+    async fn invalidate(&self, tags: &[String]) -> Result<()> {
+        let mut cache = self.cache.lock().expect("Failed to lock response cache");
+        for tag in tags {
+            for key in cache.tags.remove(tag).unwrap_or_default() {
+                cache.remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 基于 Redis 的响应缓存，适用于多实例部署
+///
+/// 标签保存为集合 `{prefix}:tag:{tag}`，过期时间不短于其中的缓存条目。
+#[cfg(feature = "redis")]
+pub struct RedisResponseCacheStore {
+    client: baizekit_redis::redis::Client,
+    conn: tokio::sync::OnceCell<baizekit_redis::redis::aio::MultiplexedConnection>,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisResponseCacheStore {
+    /// 仅在延长时更新标签集合的过期时间(新集合的 PTTL 为 -1)，兼容不支持 `PEXPIRE GT` 的 Redis 7 以下版本
+    const EXTEND_TTL: &'static str = r#"
+local ttl = tonumber(ARGV[1])
+if redis.call('PTTL', KEYS[1]) < ttl then
+    redis.call('PEXPIRE', KEYS[1], ttl)
+end
+return 0
+"#;
+
+    pub fn new(client: baizekit_redis::redis::Client) -> Self {
+        Self { client, conn: tokio::sync::OnceCell::new(), prefix: "baizekit:cache".to_string() }
+    }
+
+    /// 设置 Redis key 前缀，默认 `baizekit:cache`
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    async fn conn(&self) -> Result<baizekit_redis::redis::aio::MultiplexedConnection> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| self.client.get_multiplexed_async_connection())
+            .await?;
+        Ok(conn.clone())
+    }
+
+    fn tag_key(&self, tag: &str) -> String {
+        format!("{}:tag:{}", self.prefix, tag)
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl ResponseCacheStore for RedisResponseCacheStore {
+    async fn get(&self, key: &str) -> Result<Option<StoredResponse>> {
+        use baizekit_redis::redis::AsyncCommands;
+
+        let value: Option<String> = self.conn().await?.get(format!("{}:{}", self.prefix, key)).await?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn put(&self, key: &str, resp: &StoredResponse, tags: &[String], ttl: Duration) -> Result<()> {
+        let key = format!("{}:{}", self.prefix, key);
+        let ttl = ttl.as_millis() as u64;
+        let mut pipe = baizekit_redis::redis::pipe();
+        pipe.pset_ex(&key, serde_json::to_string(resp)?, ttl).ignore();
+        for tag in tags {
+            let tag_key = self.tag_key(tag);
+            pipe.sadd(&tag_key, &key).ignore();
+            pipe.cmd("EVAL").arg(Self::EXTEND_TTL).arg(1).arg(&tag_key).arg(ttl).ignore();
+        }
+        let _: () = pipe.query_async(&mut self.conn().await?).await?;
+        Ok(())
+    }
+
+    async fn invalidate(&self, tags: &[String]) -> Result<()> {
+        use baizekit_redis::redis::AsyncCommands;
+
+        let mut conn = self.conn().await?;
+        for tag in tags {
+            let tag_key = self.tag_key(tag);
+            let mut keys: Vec<String> = conn.smembers(&tag_key).await?;
+            keys.push(tag_key);
+            let _: () = conn.del(keys).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 响应缓存中间件状态
+#[derive(Clone)]
+pub struct ResponseCache {
+    config: Arc<CacheConfig>,
+    store: Arc<dyn ResponseCacheStore>,
+    signer: Option<PrincipalSigner>,
+}
+
+impl ResponseCache {
+    /// 校验缓存规则，`ttl_seconds` 为 0 时返回错误
+    pub fn new(config: CacheConfig, store: Arc<dyn ResponseCacheStore>) -> Result<Self> {
+        if let Some(route) = config.routes.iter().find(|route| route.ttl_seconds == 0) {
+            bail!("cache route '{}' requires ttl_seconds greater than 0", route.prefix);
+        }
+        Ok(Self { config: Arc::new(config), store, signer: None })
+    }
+
+    /// 启用身份签名时，按租户或主体缓存的请求需要携带有效签名，否则不读写缓存
+    pub fn with_signer(mut self, signer: Option<PrincipalSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    fn verified(&self, headers: &HeaderMap) -> bool {
+        let Some(signer) = &self.signer else {
+            return true;
+        };
+        [CUSTOM_ADMIN_PRINCIPAL_HEADER, CUSTOM_PRINCIPAL_HEADER]
+            .into_iter()
+            .all(|header| {
+                let Some(value) = headers.get(header).and_then(|v| v.to_str().ok()) else {
+                    return true;
+                };
+                headers
+                    .get(signature_header(header))
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|signature| signer.verify(header, value, signature))
+            })
+    }
+
+    /// 缓存键，包含维度、`Accept`(影响响应编码)、路径和查询参数
+    fn cache_key(&self, req: &Request, route: &CacheRoute) -> String {
+        let principal = || request_principal(req.headers(), self.signer.as_ref());
+        let vary = match route.vary {
+            CacheVary::Public => None,
+            CacheVary::Tenant => principal().map(|principal| format!("tenant:{}", principal.tenant_id)),
+            CacheVary::Principal => principal().map(|principal| principal.id),
+        };
+        let accept = req.headers().get(ACCEPT).and_then(|v| v.to_str().ok()).unwrap_or_default();
+        format!(
+            "{}|{}|{}",
+            vary.as_deref().unwrap_or("public"),
+            accept,
+            req.uri().path_and_query().map(|pq| pq.as_str()).unwrap_or("/")
+        )
+    }
+
+    /// 成功响应中带有 [`InvalidateCache`] 时失效对应标签
+    async fn invalidate(&self, resp: &mut Response) {
+        let Some(InvalidateCache(tags)) = resp.extensions_mut().remove::<InvalidateCache>() else {
+            return;
+        };
+        if !resp.status().is_success() || tags.is_empty() {
+            return;
+        }
+        if let Err(err) = self.store.invalidate(&tags).await {
+            tracing::error!(?tags, "response cache store error: {:?}", err);
+        }
+    }
+}
+
+/// 响应缓存中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 仅缓存匹配规则的 GET/HEAD 请求的 200 响应，命中时添加 `x-cache: HIT`；
+/// 响应带有 `ETag`，`If-None-Match` 匹配时返回 304。请求带 `Cache-Control: no-cache` 时跳过读取并刷新缓存。
+/// 处理函数返回 [`InvalidateCache`] 时，在成功响应后失效对应标签。
+pub async fn response_cache(State(cache): State<ResponseCache>, req: Request, next: Next) -> Response {
+    let route = match *req.method() {
+        Method::GET | Method::HEAD => cache.config.match_route(req.uri().path()),
+        _ => None,
+    };
+    // 伪造的身份不能读取他人的缓存，交由处理函数拒绝
+    let route = route.filter(|route| route.vary == CacheVary::Public || cache.verified(req.headers()));
+    let Some(route) = route else {
+        let mut resp = next.run(req).await;
+        cache.invalidate(&mut resp).await;
+        return resp;
+    };
+
+    let key = cache.cache_key(&req, route);
+    let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+    let no_cache = req
+        .headers()
+        .get(CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("no-cache"));
+
+    if !no_cache {
+        match cache.store.get(&key).await {
+            Ok(Some(stored)) => return cached_response(stored, if_none_match.as_ref(), "HIT"),
+            Ok(None) => {}
+            // 存储不可用时直接处理请求
+            Err(err) => tracing::error!(key, "response cache store error: {:?}", err),
+        }
+    }
+
+    let is_head = req.method() == Method::HEAD;
+    let mut resp = next.run(req).await;
+    cache.invalidate(&mut resp).await;
+
+    let cacheable = !is_head
+        && resp.status() == StatusCode::OK
+        && !resp.headers().contains_key(SET_COOKIE)
+        && !resp
+            .headers()
+            .get(CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("no-store"))
+        && resp
+            .body()
+            .size_hint()
+            .upper()
+            .is_some_and(|size| size <= cache.config.max_body_size as u64);
+    if !cacheable {
+        return resp;
+    }
+
+    let (mut parts, body) = resp.into_parts();
+    let body = match axum::body::to_bytes(body, cache.config.max_body_size).await {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!(key, "read response body failed: {}", err);
+            let status = StatusCode::INTERNAL_SERVER_ERROR;
+            let reply =
+                Reply::<()> { code: status.as_u16() as i32, message: "InternalServerError".to_string(), data: None };
+            return (status, Json(reply)).into_response();
+        }
+    };
+
+    if !parts.headers.contains_key(ETAG) {
+        let etag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(&Sha256::digest(&body)[..16]));
+        parts.headers.insert(ETAG, HeaderValue::from_str(&etag).unwrap());
+    }
+    if !parts.headers.contains_key(CACHE_CONTROL) {
+        let scope = match route.vary {
+            CacheVary::Public => "public",
+            _ => "private",
+        };
+        let value = format!("{}, max-age={}", scope, route.ttl_seconds);
+        parts.headers.insert(CACHE_CONTROL, HeaderValue::from_str(&value).unwrap());
+    }
+
+    let mut tags = route.tags.clone();
+    if let Some(CacheTags(extra)) = parts.extensions.get::<CacheTags>() {
+        tags.extend(extra.iter().cloned());
+    }
+    let headers = parts
+        .headers
+        .iter()
+        .filter(|(name, _)| ![CONTENT_LENGTH, TRANSFER_ENCODING, CONNECTION].contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let stored = StoredResponse { status: parts.status.as_u16(), headers, body: body.to_vec(), body_omitted: false };
+    let ttl = Duration::from_secs(route.ttl_seconds);
+    if let Err(err) = cache.store.put(&key, &stored, &tags, ttl).await {
+        tracing::error!(key, "response cache store error: {:?}", err);
+    }
+
+    cached_response(stored, if_none_match.as_ref(), "MISS")
+}
+
+fn cached_response(stored: StoredResponse, if_none_match: Option<&HeaderValue>, status: &'static str) -> Response {
+    let mut headers = HeaderMap::new();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert("x-cache", HeaderValue::from_static(status));
+
+    let not_modified = match (if_none_match.and_then(|v| v.to_str().ok()), headers.get(ETAG)) {
+        (Some(candidates), Some(etag)) => candidates
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag),
+        _ => false,
+    };
+
+    let mut resp = match not_modified {
+        true => {
+            headers.remove(axum::http::header::CONTENT_TYPE);
+            let mut resp = Response::new(Body::empty());
+            *resp.status_mut() = StatusCode::NOT_MODIFIED;
+            resp
+        }
+        false => {
+            let mut resp = Response::new(Body::from(stored.body));
+            *resp.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+            resp
+        }
+    };
+    *resp.headers_mut() = headers;
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::Router;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_response_cache() {
+        let counter = Arc::new(AtomicU32::new(0));
+        let handler_counter = counter.clone();
+        let config = CacheConfig {
+            routes: vec![CacheRoute {
+                prefix: "/products".to_string(),
+                vary: CacheVary::Tenant,
+                ttl_seconds: 60,
+                tags: vec!["products".to_string()],
+            }],
+            ..Default::default()
+        };
+        let state = ResponseCache::new(config, Arc::new(MemoryResponseCacheStore::new(16))).unwrap();
+        let router = Router::new()
+            .route(
+                "/products",
+                get(move || async move {
+                    let n = handler_counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    (CacheTags::new(["product:1"]), format!("v{}", n))
+                })
+                .post(|| async { (InvalidateCache::new(["product:1"]), "OK") }),
+            )
+            .layer(axum::middleware::from_fn_with_state(state, response_cache));
+
+        let send = |method: Method, tenant: &str, etag: Option<&str>| {
+            let principal = format!(r#"{{"id":1,"account":"a","tenant_id":"{}"}}"#, tenant);
+            let mut req = Request::builder()
+                .method(method)
+                .uri("/products?page=1")
+                .header(CUSTOM_PRINCIPAL_HEADER, principal);
+            if let Some(etag) = etag {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+            router.clone().oneshot(req.body(Body::empty()).unwrap())
+        };
+        let body = |resp: Response| async move {
+            String::from_utf8(axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+        };
+
+        let first = send(Method::GET, "t1", None).await.unwrap();
+        assert_eq!(first.headers()["x-cache"], "MISS");
+        assert_eq!(first.headers()[CACHE_CONTROL], "private, max-age=60");
+        let etag = first.headers()[ETAG].to_str().unwrap().to_string();
+        assert_eq!(body(first).await, "v1");
+
+        let hit = send(Method::GET, "t1", None).await.unwrap();
+        assert_eq!(hit.headers()["x-cache"], "HIT");
+        assert_eq!(body(hit).await, "v1");
+
+        let not_modified = send(Method::GET, "t1", Some(&etag)).await.unwrap();
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+
+        // 不同租户使用各自的缓存
+        assert_eq!(body(send(Method::GET, "t2", None).await.unwrap()).await, "v2");
+
+        // 处理函数追加的标签失效后重新查询
+        assert_eq!(send(Method::POST, "t1", None).await.unwrap().status(), StatusCode::OK);
+        let refreshed = send(Method::GET, "t1", None).await.unwrap();
+        assert_eq!(refreshed.headers()["x-cache"], "MISS");
+        assert_eq!(body(refreshed).await, "v3");
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_forged_principal() {
+        let config = CacheConfig {
+            routes: vec![CacheRoute {
+                prefix: "/me".to_string(),
+                vary: CacheVary::Principal,
+                ttl_seconds: 60,
+                tags: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        let counter = Arc::new(AtomicU32::new(0));
+        let handler_counter = counter.clone();
+        let signer = PrincipalSigner::from_secret("s3cret");
+        let state = ResponseCache::new(config, Arc::new(MemoryResponseCacheStore::new(16)))
+            .unwrap()
+            .with_signer(Some(signer.clone()));
+        let router = Router::new()
+            .route(
+                "/me",
+                get(move || async move { format!("v{}", handler_counter.fetch_add(1, Ordering::SeqCst) + 1) }),
+            )
+            .layer(axum::middleware::from_fn_with_state(state, response_cache));
+        let principal =
+            crate::extract::EndUserPrincipal { id: 1, account: "a".to_string(), tenant_id: "t".to_string() };
+
+        let mut req = Request::get("/me").body(Body::empty()).unwrap();
+        req.headers_mut().extend(signer.headers(&principal));
+        assert_eq!(router.clone().oneshot(req).await.unwrap().headers()["x-cache"], "MISS");
+
+        // 伪造的身份不能读取他人的缓存
+        let req = Request::get("/me")
+            .header(CUSTOM_PRINCIPAL_HEADER, serde_json::to_string(&principal).unwrap())
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert!(!resp.headers().contains_key("x-cache"));
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"v2");
+    }
+
+    #[test]
+    fn test_route_config() {
+        let route: CacheRoute = serde_json::from_str(r#"{"prefix":"/products","ttl_seconds":0}"#).unwrap();
+        assert_eq!(route.vary, CacheVary::Principal);
+
+        let config = CacheConfig { routes: vec![route], ..Default::default() };
+        assert!(ResponseCache::new(config.clone(), Arc::new(MemoryResponseCacheStore::new(16))).is_err());
+        assert_eq!(config.match_route("/products/1").map(|r| r.prefix.as_str()), Some("/products"));
+        assert!(config.match_route("/productsx").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_lru() {
+        let store = MemoryResponseCacheStore::new(2);
+        let resp = StoredResponse { status: 200, headers: Vec::new(), body: b"ok".to_vec(), body_omitted: false };
+        let ttl = Duration::from_secs(60);
+        store.put("a", &resp, &["t".to_string()], ttl).await.unwrap();
+        store.put("b", &resp, &[], ttl).await.unwrap();
+        store.get("a").await.unwrap();
+        store.put("c", &resp, &[], ttl).await.unwrap();
+
+        assert!(store.get("a").await.unwrap().is_some());
+        assert!(store.get("b").await.unwrap().is_none());
+
+        store.invalidate(&["t".to_string()]).await.unwrap();
+        assert!(store.get("a").await.unwrap().is_none());
+        assert!(store.get("c").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,137 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use baizekit_app::anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS 配置，读取自 `axum.{label}.cors`，需配合 `AxumComponentBuilder::with_cors` 启用
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// 允许的源，为空时允许任意源；`with_cors` 传入的源优先
+    pub allow_origins: Vec<String>,
+    /// 允许的方法
+    pub allow_methods: Vec<String>,
+    /// 允许的请求头
+    pub allow_headers: Vec<String>,
+    /// 允许浏览器读取的响应头
+    pub expose_headers: Vec<String>,
+    /// 是否允许携带 Cookie 等凭据，此时不能允许任意源
+    pub allow_credentials: bool,
+    /// 预检请求的缓存时间(秒)
+    pub max_age_seconds: Option<u64>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allow_origins: Vec::new(),
+            allow_methods: ["GET", "POST", "PUT", "DELETE"].map(String::from).to_vec(),
+            allow_headers: ["authorization", "accept", "content-type"].map(String::from).to_vec(),
+            expose_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_seconds: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// 构建 CORS layer，`allow_origin` 为 None 时使用配置中的源
+    pub fn layer(&self, allow_origin: Option<AllowOrigin>) -> Result<CorsLayer> {
+        let any_origin = match &allow_origin {
+            Some(origin) => is_any(origin),
+            None => self.allow_origins.is_empty(),
+        };
+        if any_origin && self.allow_credentials {
+            bail!("cors allow_credentials cannot be used with any origin, specify allow_origins instead");
+        }
+
+        let allow_origin = match allow_origin {
+            Some(origin) => origin,
+            None if any_origin => AllowOrigin::any(),
+            None => {
+                let origins = self
+                    .allow_origins
+                    .iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(origin).with_context(|| format!("invalid cors origin '{}'", origin))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                AllowOrigin::list(origins)
+            }
+        };
+        let methods = self
+            .allow_methods
+            .iter()
+            .map(|method| Method::from_str(method).with_context(|| format!("invalid cors method '{}'", method)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut layer = CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(methods)
+            .allow_headers(parse_headers(&self.allow_headers)?)
+            .expose_headers(parse_headers(&self.expose_headers)?)
+            .allow_credentials(self.allow_credentials);
+        if let Some(secs) = self.max_age_seconds {
+            layer = layer.max_age(Duration::from_secs(secs));
+        }
+        Ok(layer)
+    }
+}
+
+/// `AllowOrigin` 未公开是否为通配，与 [`AllowOrigin::any`] 的调试输出比较
+fn is_any(origin: &AllowOrigin) -> bool {
+    format!("{:?}", origin) == format!("{:?}", AllowOrigin::any())
+}
+
+fn parse_headers(headers: &[String]) -> Result<Vec<HeaderName>> {
+    headers
+        .iter()
+        .map(|name| HeaderName::from_str(name).with_context(|| format!("invalid cors header '{}'", name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cors_config() {
+        let config = CorsConfig {
+            allow_origins: vec!["https://admin.example.com".to_string()],
+            allow_credentials: true,
+            max_age_seconds: Some(600),
+            ..Default::default()
+        };
+        let router = Router::new()
+            .route("/api", get(|| async { "OK" }))
+            .layer(config.layer(None).unwrap());
+
+        let req = Request::options("/api")
+            .header("origin", "https://admin.example.com")
+            .header("access-control-request-method", "POST")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.headers()["access-control-allow-origin"], "https://admin.example.com");
+        assert_eq!(resp.headers()["access-control-allow-credentials"], "true");
+        assert_eq!(resp.headers()["access-control-max-age"], "600");
+
+        let config = CorsConfig { allow_credentials: true, ..Default::default() };
+        assert!(config.layer(None).is_err());
+        assert!(config.layer(Some(AllowOrigin::any())).is_err());
+        assert!(
+            config
+                .layer(Some(AllowOrigin::exact(HeaderValue::from_static("https://a.example.com"))))
+                .is_ok()
+        );
+    }
+}
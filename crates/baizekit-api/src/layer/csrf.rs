@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::header::{COOKIE, SET_COOKIE};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+
+use crate::layer::rate_limit::path_has_prefix;
+use crate::response::Reply;
+
+/// 双重提交 Cookie 的 CSRF 防护配置，读取自 `axum.{label}.csrf`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CsrfConfig {
+    /// 保存令牌的 Cookie 名称，前端读取后通过 `header_name` 回传
+    pub cookie_name: String,
+    /// 提交令牌的请求头名称
+    pub header_name: String,
+    /// Cookie 的 `Path`
+    pub cookie_path: String,
+    /// Cookie 的 `SameSite`
+    pub same_site: String,
+    /// Cookie 是否带 `Secure`
+    pub secure: bool,
+    /// 不校验的路径前缀，按路径段匹配，例如第三方回调
+    pub exempt_paths: Vec<String>,
+}
+
+impl Default for CsrfConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: "csrf_token".to_string(),
+            header_name: "x-csrf-token".to_string(),
+            cookie_path: "/".to_string(),
+            same_site: "Strict".to_string(),
+            secure: true,
+            exempt_paths: Vec::new(),
+        }
+    }
+}
+
+/// CSRF 中间件状态
+#[derive(Clone)]
+pub struct Csrf {
+    config: Arc<CsrfConfig>,
+}
+
+impl Csrf {
+    pub fn new(config: CsrfConfig) -> Self {
+        Self { config: Arc::new(config) }
+    }
+
+    fn cookie_token<'a>(&self, req: &'a Request) -> Option<&'a str> {
+        req.headers()
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.config.cookie_name)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn set_cookie(&self, token: &str) -> Option<HeaderValue> {
+        let mut cookie = format!(
+            "{}={}; Path={}; SameSite={}",
+            self.config.cookie_name, token, self.config.cookie_path, self.config.same_site
+        );
+        if self.config.secure {
+            cookie.push_str("; Secure");
+        }
+        HeaderValue::from_str(&cookie).ok()
+    }
+}
+
+/// CSRF 中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 安全方法的请求在缺少令牌时下发 Cookie；其他方法要求请求头中的令牌与 Cookie 一致，否则返回 403。
+/// 不带 Cookie 的请求(例如使用 Bearer Token 的接口调用)无法被跨站伪造，不做校验。
+pub async fn csrf(State(csrf): State<Csrf>, req: Request, next: Next) -> Response {
+    let safe = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE);
+    let cookie = csrf.cookie_token(&req).map(str::to_string);
+
+    if safe {
+        let mut resp = next.run(req).await;
+        if cookie.is_none()
+            && let Some(value) = csrf.set_cookie(&uuid::Uuid::new_v4().simple().to_string())
+        {
+            resp.headers_mut().append(SET_COOKIE, value);
+        }
+        return resp;
+    }
+
+    let path = req.uri().path();
+    let exempt = csrf.config.exempt_paths.iter().any(|prefix| path_has_prefix(path, prefix));
+    if exempt || !req.headers().contains_key(COOKIE) {
+        return next.run(req).await;
+    }
+
+    let header = req
+        .headers()
+        .get(csrf.config.header_name.as_str())
+        .and_then(|v| v.to_str().ok());
+    match (cookie.as_deref(), header) {
+        (Some(cookie), Some(header)) if constant_time_eq(cookie.as_bytes(), header.as_bytes()) => next.run(req).await,
+        _ => {
+            tracing::warn!(method = %req.method(), path, "csrf token mismatch");
+            let status = StatusCode::FORBIDDEN;
+            let reply =
+                Reply::<()> { code: status.as_u16() as i32, message: "CSRF token mismatch".to_string(), data: None };
+            (status, reply).into_response()
+        }
+    }
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::{get, post};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_csrf() {
+        let config = CsrfConfig { exempt_paths: vec!["/webhook".to_string()], ..Default::default() };
+        let router = Router::new()
+            .route("/form", get(|| async { "OK" }).post(|| async { "OK" }))
+            .route("/webhook/pay", post(|| async { "OK" }))
+            .route("/webhooks-admin/delete", post(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(Csrf::new(config), csrf));
+        let send = |req: Request| router.clone().oneshot(req);
+
+        let resp = send(Request::get("/form").body(Body::empty()).unwrap()).await.unwrap();
+        let cookie = resp.headers()[SET_COOKIE].to_str().unwrap().to_string();
+        assert!(cookie.starts_with("csrf_token=") && cookie.ends_with("; Secure"));
+        let token = cookie.split(';').next().unwrap().trim_start_matches("csrf_token=").to_string();
+
+        // 已有令牌时不重复下发
+        let req = Request::get("/form")
+            .header(COOKIE, format!("csrf_token={}", token))
+            .body(Body::empty())
+            .unwrap();
+        assert!(send(req).await.unwrap().headers().get(SET_COOKIE).is_none());
+
+        let req = Request::post("/form")
+            .header(COOKIE, format!("session=1; csrf_token={}", token))
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        let req = Request::post("/form")
+            .header(COOKIE, format!("session=1; csrf_token={}", token))
+            .header("x-csrf-token", &token)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::OK);
+
+        let req = Request::post("/form").body(Body::empty()).unwrap();
+        assert_eq!(send(req).await.unwrap().status(), StatusCode::OK);
+
+        // 豁免路径按路径段匹配
+        for (uri, status) in [
+            ("/webhook/pay", StatusCode::OK),
+            ("/webhooks-admin/delete", StatusCode::FORBIDDEN),
+        ] {
+            let req = Request::post(uri).header(COOKIE, "session=1").body(Body::empty()).unwrap();
+            assert_eq!(send(req).await.unwrap().status(), status, "{}", uri);
+        }
+    }
+}
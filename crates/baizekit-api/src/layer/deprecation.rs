@@ -0,0 +1,123 @@
+use axum::extract::{Request, State};
+use axum::http::header::{InvalidHeaderValue, LINK};
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use utoipa::openapi::{Deprecated, OpenApi};
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// 已弃用的 API 版本，为响应添加 `Deprecation`、`Sunset` 和 `Link` 响应头
+///
+/// 参考 RFC 9745 和 RFC 8594。
+#[derive(Debug, Clone)]
+pub struct ApiDeprecation {
+    /// 弃用时间，Unix 时间戳（秒）
+    since: i64,
+    /// 下线时间，HTTP-date 格式，例如 `Wed, 31 Dec 2025 23:59:59 GMT`
+    sunset: Option<String>,
+    /// 替代版本的地址
+    successor: Option<String>,
+    /// 创建时校验并生成的响应头
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl ApiDeprecation {
+    /// 创建弃用配置，`sunset` 或 `successor` 不是合法的响应头值时返回错误
+    pub fn new(since: i64, sunset: Option<&str>, successor: Option<&str>) -> Result<Self, InvalidHeaderValue> {
+        let mut headers = vec![(DEPRECATION, HeaderValue::from_str(&format!("@{}", since))?)];
+        if let Some(sunset) = sunset {
+            headers.push((SUNSET, HeaderValue::from_str(sunset)?));
+        }
+        if let Some(successor) = successor {
+            let link = format!("<{}>; rel=\"successor-version\"", successor);
+            headers.push((LINK, HeaderValue::from_str(&link)?));
+        }
+        Ok(Self { since, sunset: sunset.map(str::to_string), successor: successor.map(str::to_string), headers })
+    }
+
+    pub fn since(&self) -> i64 {
+        self.since
+    }
+
+    pub fn sunset(&self) -> Option<&str> {
+        self.sunset.as_deref()
+    }
+
+    pub fn successor(&self) -> Option<&str> {
+        self.successor.as_deref()
+    }
+
+    /// 需要添加的响应头
+    pub fn headers(&self) -> &[(HeaderName, HeaderValue)] {
+        &self.headers
+    }
+
+    /// 将文档中的接口标记为已弃用
+    pub fn apply_openapi(&self, openapi: &mut OpenApi) {
+        for path_item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut path_item.get,
+                &mut path_item.put,
+                &mut path_item.post,
+                &mut path_item.delete,
+                &mut path_item.options,
+                &mut path_item.head,
+                &mut path_item.patch,
+                &mut path_item.trace,
+            ];
+            for operation in operations.into_iter().flatten() {
+                operation.deprecated = Some(Deprecated::True);
+            }
+        }
+    }
+}
+
+/// 弃用响应头中间件，已存在的响应头不会被覆盖
+pub async fn api_deprecation(State(deprecation): State<ApiDeprecation>, req: Request, next: Next) -> Response {
+    let mut resp = next.run(req).await;
+    for (name, value) in deprecation.headers() {
+        resp.headers_mut().entry(name.clone()).or_insert_with(|| value.clone());
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+    use utoipa::openapi::path::{HttpMethod, OperationBuilder, PathsBuilder};
+    use utoipa::openapi::{Info, PathItem};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_api_deprecation() {
+        let deprecation =
+            ApiDeprecation::new(1735689600, Some("Wed, 31 Dec 2025 23:59:59 GMT"), Some("/v2/users")).unwrap();
+        let router = Router::new()
+            .route("/users", get(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(deprecation.clone(), api_deprecation));
+
+        let resp = router
+            .oneshot(Request::get("/users").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.headers()["deprecation"], "@1735689600");
+        assert_eq!(resp.headers()["sunset"], "Wed, 31 Dec 2025 23:59:59 GMT");
+        assert_eq!(resp.headers()["link"], "</v2/users>; rel=\"successor-version\"");
+
+        let paths = PathsBuilder::new()
+            .path("/users", PathItem::new(HttpMethod::Get, OperationBuilder::new().build()))
+            .build();
+        let mut openapi = OpenApi::new(Info::new("test", "1.0"), paths);
+        deprecation.apply_openapi(&mut openapi);
+        let operation = openapi.paths.paths["/users"].get.as_ref().unwrap();
+        assert!(matches!(operation.deprecated, Some(Deprecated::True)));
+
+        assert!(ApiDeprecation::new(1735689600, Some("invalid\nsunset"), None).is_err());
+    }
+}
@@ -0,0 +1,668 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::header::{CONNECTION, CONTENT_LENGTH, TRANSFER_ENCODING};
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use baizekit_app::anyhow::Result;
+use baizekit_app::async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::extract::{PrincipalSigner, request_principal};
+use crate::response::Reply;
+
+/// 幂等配置，读取自 `axum.{label}.idempotency`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct IdempotencyConfig {
+    /// 幂等键请求头
+    pub header: String,
+    /// 生效的请求方法
+    pub methods: Vec<String>,
+    /// 已完成响应的保存时间(秒)
+    pub ttl_seconds: u64,
+    /// 处理中标记的保存时间(秒)，请求异常中断时到期后允许重试
+    pub in_flight_ttl_seconds: u64,
+    /// 可保存的响应体大小上限(字节)，超出或流式响应只保存状态码，重放时返回 409
+    pub max_body_size: usize,
+    /// 计算请求指纹时读取的请求体大小上限(字节)，超出时返回 413
+    pub max_request_body_size: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            header: "idempotency-key".to_string(),
+            methods: vec!["POST".to_string()],
+            ttl_seconds: 24 * 3600,
+            in_flight_ttl_seconds: 60,
+            max_body_size: 1 << 20,
+            max_request_body_size: 1 << 20,
+        }
+    }
+}
+
+/// 保存的响应
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// 响应体过大或为流式响应时未保存响应体，仅记录请求已完成
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub body_omitted: bool,
+}
+
+impl StoredResponse {
+    /// 占用的内存大小(字节)，用于限制进程内存储
+    fn size(&self) -> usize {
+        self.body.len() + self.headers.iter().map(|(name, value)| name.len() + value.len()).sum::<usize>()
+    }
+
+    fn into_response(self) -> Response {
+        if self.body_omitted {
+            let message = format!("Request Already Completed With Status {}", self.status);
+            let mut resp = reply(StatusCode::CONFLICT, &message);
+            resp.headers_mut()
+                .insert("idempotent-replayed", HeaderValue::from_static("true"));
+            return resp;
+        }
+
+        let mut resp = Response::new(Body::from(self.body));
+        *resp.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                resp.headers_mut().append(name, value);
+            }
+        }
+        resp.headers_mut()
+            .insert("idempotent-replayed", HeaderValue::from_static("true"));
+        resp
+    }
+}
+
+/// 请求指纹，同一幂等键只能用于相同的请求
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RequestFingerprint {
+    pub method: String,
+    pub path: String,
+    /// 请求体的 SHA-256，base64url 编码
+    pub body_sha256: String,
+}
+
+impl RequestFingerprint {
+    fn new(req: &axum::http::request::Parts, body: &[u8]) -> Self {
+        Self {
+            method: req.method.to_string(),
+            path: req.uri.path().to_string(),
+            body_sha256: URL_SAFE_NO_PAD.encode(Sha256::digest(body)),
+        }
+    }
+}
+
+/// 幂等键状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyState {
+    /// 首次请求，已标记为处理中
+    Started,
+    /// 原请求仍在处理中
+    InFlight(RequestFingerprint),
+    /// 原请求已完成
+    Completed(RequestFingerprint, StoredResponse),
+}
+
+/// 幂等键存储
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// 键不存在时标记为处理中并返回 [`IdempotencyState::Started`]，否则返回当前状态
+    async fn begin(&self, key: &str, fingerprint: &RequestFingerprint, ttl: Duration) -> Result<IdempotencyState>;
+
+    /// 保存已完成的响应
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+        resp: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<()>;
+
+    /// 删除键，允许客户端重试
+    async fn release(&self, key: &str) -> Result<()>;
+}
+
+/// 保存的幂等记录，`response` 为空表示处理中
+#[derive(Clone, Deserialize, Serialize)]
+struct Record {
+    fingerprint: RequestFingerprint,
+    response: Option<StoredResponse>,
+}
+
+impl From<Record> for IdempotencyState {
+    fn from(record: Record) -> Self {
+        match record.response {
+            Some(resp) => IdempotencyState::Completed(record.fingerprint, resp),
+            None => IdempotencyState::InFlight(record.fingerprint),
+        }
+    }
+}
+
+impl Record {
+    fn size(&self, key: &str) -> usize {
+        key.len() + self.response.as_ref().map_or(0, StoredResponse::size)
+    }
+}
+
+struct MemoryEntries {
+    entries: lru::LruCache<String, (Record, Instant)>,
+    /// 所有记录占用的字节数
+    size: usize,
+    max_size: usize,
+}
+
+impl MemoryEntries {
+    fn insert(&mut self, key: &str, record: Record, expires_at: Instant) {
+        self.size += record.size(key);
+        if let Some((old_key, (old, _))) = self.entries.push(key.to_string(), (record, expires_at)) {
+            self.size -= old.size(&old_key);
+        }
+        // 超出大小上限时淘汰最久未访问的记录，至少保留刚写入的记录
+        while self.size > self.max_size && self.entries.len() > 1 {
+            match self.entries.pop_lru() {
+                Some((old_key, (old, _))) => self.size -= old.size(&old_key),
+                None => break,
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((old, _)) = self.entries.pop(key) {
+            self.size -= old.size(key);
+        }
+    }
+}
+
+/// 进程内幂等键存储，仅适用于单实例部署
+///
+/// 记录数量或总大小超过上限时淘汰最久未访问的记录，被淘汰的幂等键再次使用时会重新执行请求。
+pub struct MemoryIdempotencyStore {
+    entries: Mutex<MemoryEntries>,
+}
+
+impl Default for MemoryIdempotencyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryIdempotencyStore {
+    /// 默认的记录数量上限
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+    /// 默认的记录总大小上限(字节)
+    pub const DEFAULT_MAX_SIZE: usize = 64 << 20;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// `capacity` 为记录数量上限，`max_size` 为记录总大小上限(字节)
+    pub fn with_capacity(capacity: usize, max_size: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        let entries = MemoryEntries { entries: lru::LruCache::new(capacity), size: 0, max_size };
+        Self { entries: Mutex::new(entries) }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &RequestFingerprint, ttl: Duration) -> Result<IdempotencyState> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().expect("Failed to lock idempotency entries");
+
+        match entries.entries.get(key) {
+            Some((record, expires_at)) if *expires_at > now => Ok(record.clone().into()),
+            _ => {
+                let record = Record { fingerprint: fingerprint.clone(), response: None };
+                entries.insert(key, record, now + ttl);
+                Ok(IdempotencyState::Started)
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+        resp: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<()> {
+        let record = Record { fingerprint: fingerprint.clone(), response: Some(resp.clone()) };
+        let mut entries = self.entries.lock().expect("Failed to lock idempotency entries");
+        entries.insert(key, record, Instant::now() + ttl);
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        self.entries.lock().expect("Failed to lock idempotency entries").remove(key);
+        Ok(())
+    }
+}
+
+/// 基于 Redis 的幂等键存储，适用于多实例部署
+#[cfg(feature = "redis")]
+pub struct RedisIdempotencyStore {
+    client: baizekit_redis::redis::Client,
+    conn: tokio::sync::OnceCell<baizekit_redis::redis::aio::MultiplexedConnection>,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisIdempotencyStore {
+    pub fn new(client: baizekit_redis::redis::Client) -> Self {
+        Self { client, conn: tokio::sync::OnceCell::new(), prefix: "baizekit:idempotency".to_string() }
+    }
+
+    /// 设置 Redis key 前缀，默认 `baizekit:idempotency`
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    async fn conn(&self) -> Result<baizekit_redis::redis::aio::MultiplexedConnection> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| self.client.get_multiplexed_async_connection())
+            .await?;
+        Ok(conn.clone())
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl IdempotencyStore for RedisIdempotencyStore {
+    async fn begin(&self, key: &str, fingerprint: &RequestFingerprint, ttl: Duration) -> Result<IdempotencyState> {
+        use baizekit_redis::redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+
+        let key = format!("{}:{}", self.prefix, key);
+        let mut conn = self.conn().await?;
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(ttl.as_millis() as u64));
+        let record = Record { fingerprint: fingerprint.clone(), response: None };
+        let started: Option<String> = conn.set_options(&key, serde_json::to_string(&record)?, options).await?;
+        if started.is_some() {
+            return Ok(IdempotencyState::Started);
+        }
+
+        let value: Option<String> = conn.get(&key).await?;
+        match value {
+            Some(json) => Ok(serde_json::from_str::<Record>(&json)?.into()),
+            // 在两次命令之间过期，按处理中返回，由客户端重试
+            None => Ok(IdempotencyState::InFlight(fingerprint.clone())),
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &RequestFingerprint,
+        resp: &StoredResponse,
+        ttl: Duration,
+    ) -> Result<()> {
+        use baizekit_redis::redis::AsyncCommands;
+
+        let record = Record { fingerprint: fingerprint.clone(), response: Some(resp.clone()) };
+        let json = serde_json::to_string(&record)?;
+        let _: () = self
+            .conn()
+            .await?
+            .pset_ex(format!("{}:{}", self.prefix, key), json, ttl.as_millis() as u64)
+            .await?;
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        use baizekit_redis::redis::AsyncCommands;
+
+        let _: () = self.conn().await?.del(format!("{}:{}", self.prefix, key)).await?;
+        Ok(())
+    }
+}
+
+/// 幂等中间件状态
+#[derive(Clone)]
+pub struct Idempotency {
+    config: Arc<IdempotencyConfig>,
+    store: Arc<dyn IdempotencyStore>,
+    signer: Option<PrincipalSigner>,
+}
+
+impl Idempotency {
+    pub fn new(config: IdempotencyConfig, store: Arc<dyn IdempotencyStore>) -> Self {
+        Self { config: Arc::new(config), store, signer: None }
+    }
+
+    /// 启用身份签名时，签名无效的身份按匿名处理
+    pub fn with_signer(mut self, signer: Option<PrincipalSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    fn applies_to(&self, req: &Request) -> bool {
+        self.config
+            .methods
+            .iter()
+            .any(|method| method.eq_ignore_ascii_case(req.method().as_str()))
+    }
+}
+
+/// 幂等中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 同一主体使用相同幂等键的请求只执行一次，重放时返回保存的响应并添加 `idempotent-replayed: true`；
+/// 原请求处理中时返回 409，幂等键用于方法、路径或请求体不同的请求时返回 422。
+/// 响应体超过 `max_body_size` 或为流式响应时只记录请求已完成，重放时返回 409 而不会重新执行请求。
+/// 5xx 响应不保存，客户端可使用相同幂等键重试。未识别身份的请求不做幂等处理，避免不同调用方共用幂等键。
+pub async fn idempotency(State(idempotency): State<Idempotency>, req: Request, next: Next) -> Response {
+    if !idempotency.applies_to(&req) {
+        return next.run(req).await;
+    }
+    let Some(value) = req.headers().get(idempotency.config.header.as_str()) else {
+        return next.run(req).await;
+    };
+    let idempotency_key = match value.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= 255 => key,
+        _ => return reply(StatusCode::BAD_REQUEST, "Invalid Idempotency Key"),
+    };
+
+    let Some(principal) = request_principal(req.headers(), idempotency.signer.as_ref()) else {
+        return next.run(req).await;
+    };
+    let key = format!("{}|{}", principal.id, idempotency_key);
+    let config = &idempotency.config;
+    let store = &idempotency.store;
+
+    let (parts, body) = req.into_parts();
+    let body = match axum::body::to_bytes(body, config.max_request_body_size).await {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::warn!(key, "read request body failed: {}", err);
+            return reply(StatusCode::PAYLOAD_TOO_LARGE, "Payload Too Large");
+        }
+    };
+    let fingerprint = RequestFingerprint::new(&parts, &body);
+    let req = Request::from_parts(parts, Body::from(body));
+
+    match store
+        .begin(&key, &fingerprint, Duration::from_secs(config.in_flight_ttl_seconds))
+        .await
+    {
+        Ok(IdempotencyState::Started) => {}
+        Ok(IdempotencyState::InFlight(original) | IdempotencyState::Completed(original, _))
+            if original != fingerprint =>
+        {
+            tracing::warn!(key, "idempotency key reused with a different request");
+            return reply(StatusCode::UNPROCESSABLE_ENTITY, "Idempotency Key Reused");
+        }
+        Ok(IdempotencyState::InFlight(_)) => return reply(StatusCode::CONFLICT, "Request In Progress"),
+        Ok(IdempotencyState::Completed(_, resp)) => {
+            tracing::info!(key, "idempotent replay");
+            return resp.into_response();
+        }
+        Err(err) => {
+            // 存储不可用时放行，与限流保持一致
+            tracing::error!(key, "idempotency store error: {:?}", err);
+            return next.run(req).await;
+        }
+    }
+
+    let resp = next.run(req).await;
+    if resp.status().is_server_error() {
+        if let Err(err) = store.release(&key).await {
+            tracing::error!(key, "idempotency store error: {:?}", err);
+        }
+        return resp;
+    }
+
+    let ttl = Duration::from_secs(config.ttl_seconds);
+    let storable = resp
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= config.max_body_size as u64);
+    if !storable {
+        // 响应体无法保存时仍记录请求已完成，避免重试时重复执行
+        let (parts, body) = resp.into_parts();
+        let omitted = omitted_response(&parts);
+        if let Err(err) = store.complete(&key, &fingerprint, &omitted, ttl).await {
+            tracing::error!(key, "idempotency store error: {:?}", err);
+        }
+        return Response::from_parts(parts, body);
+    }
+
+    let (parts, body) = resp.into_parts();
+    let body = match axum::body::to_bytes(body, config.max_body_size).await {
+        Ok(body) => body,
+        Err(err) => {
+            if let Err(err) = store.complete(&key, &fingerprint, &omitted_response(&parts), ttl).await {
+                tracing::error!(key, "idempotency store error: {:?}", err);
+            }
+            tracing::error!(key, "read response body failed: {}", err);
+            return reply(StatusCode::INTERNAL_SERVER_ERROR, "InternalServerError");
+        }
+    };
+
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        headers: stored_headers(&parts),
+        body: body.to_vec(),
+        body_omitted: false,
+    };
+    if let Err(err) = store.complete(&key, &fingerprint, &stored, ttl).await {
+        tracing::error!(key, "idempotency store error: {:?}", err);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn stored_headers(parts: &axum::http::response::Parts) -> Vec<(String, String)> {
+    parts
+        .headers
+        .iter()
+        .filter(|(name, _)| ![CONTENT_LENGTH, TRANSFER_ENCODING, CONNECTION].contains(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// 只记录状态码和响应头的完成标记
+fn omitted_response(parts: &axum::http::response::Parts) -> StoredResponse {
+    StoredResponse {
+        status: parts.status.as_u16(),
+        headers: stored_headers(parts),
+        body: Vec::new(),
+        body_omitted: true,
+    }
+}
+
+fn reply(status: StatusCode, message: &str) -> Response {
+    let reply = Reply::<()> { code: status.as_u16() as i32, message: message.to_string(), data: None };
+    (status, Json(reply)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::Router;
+    use axum::routing::post;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::extract::{CUSTOM_PRINCIPAL_HEADER, EndUserPrincipal};
+
+    #[tokio::test]
+    async fn test_idempotent_replay_and_conflict() {
+        let counter = Arc::new(AtomicU32::new(0));
+        let handler_counter = counter.clone();
+        let state = Idempotency::new(IdempotencyConfig::default(), Arc::new(MemoryIdempotencyStore::new()));
+        let router = Router::new()
+            .route(
+                "/pay",
+                post(move || async move {
+                    let n = handler_counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    (StatusCode::CREATED, [("x-order", n.to_string())], format!("order-{}", n))
+                }),
+            )
+            .route(
+                "/slow",
+                post(|| async {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    "done"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(state, idempotency));
+
+        let request = |uri: &str, key: &str, user: i32| {
+            let principal = format!(r#"{{"id":{},"account":"a","tenant_id":"t"}}"#, user);
+            Request::post(uri)
+                .header("idempotency-key", key)
+                .header(CUSTOM_PRINCIPAL_HEADER, principal)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let body = |resp: Response| async move {
+            String::from_utf8(axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+        };
+
+        let first = router.clone().oneshot(request("/pay", "k1", 1)).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert_eq!(body(first).await, "order-1");
+
+        let replay = router.clone().oneshot(request("/pay", "k1", 1)).await.unwrap();
+        assert_eq!(replay.status(), StatusCode::CREATED);
+        assert_eq!(replay.headers()["x-order"], "1");
+        assert_eq!(replay.headers()["idempotent-replayed"], "true");
+        assert_eq!(body(replay).await, "order-1");
+
+        // 不同主体使用相同幂等键互不影响
+        let other = router.clone().oneshot(request("/pay", "k1", 2)).await.unwrap();
+        assert_eq!(body(other).await, "order-2");
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        let slow = tokio::spawn(router.clone().oneshot(request("/slow", "k2", 1)));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let conflict = router.clone().oneshot(request("/slow", "k2", 1)).await.unwrap();
+        assert_eq!(conflict.status(), StatusCode::CONFLICT);
+        assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+
+        // 幂等键用于不同的请求
+        let reused = router.clone().oneshot(request("/slow", "k1", 1)).await.unwrap();
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let mut changed_body = request("/pay", "k1", 1);
+        *changed_body.body_mut() = Body::from("amount=2");
+        let reused = router.clone().oneshot(changed_body).await.unwrap();
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // 未识别身份的请求不做幂等处理
+        let anonymous = Request::post("/pay").header("idempotency-key", "k3").body(Body::empty());
+        let resp = router.clone().oneshot(anonymous.unwrap()).await.unwrap();
+        assert!(!resp.headers().contains_key("idempotent-replayed"));
+        let anonymous = Request::post("/pay").header("idempotency-key", "k3").body(Body::empty());
+        assert_eq!(body(router.oneshot(anonymous.unwrap()).await.unwrap()).await, "order-4");
+    }
+
+    #[tokio::test]
+    async fn test_large_response_is_not_re_executed() {
+        let counter = Arc::new(AtomicU32::new(0));
+        let handler_counter = counter.clone();
+        let config = IdempotencyConfig { max_body_size: 4, ..Default::default() };
+        let state = Idempotency::new(config, Arc::new(MemoryIdempotencyStore::new()));
+        let router = Router::new()
+            .route(
+                "/pay",
+                post(move || async move { format!("order-{}", handler_counter.fetch_add(1, Ordering::SeqCst) + 1) }),
+            )
+            .layer(axum::middleware::from_fn_with_state(state, idempotency));
+        let request = || {
+            Request::post("/pay")
+                .header("idempotency-key", "k1")
+                .header(CUSTOM_PRINCIPAL_HEADER, r#"{"id":1,"account":"a","tenant_id":"t"}"#)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let first = router.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let replay = router.oneshot(request()).await.unwrap();
+        assert_eq!(replay.status(), StatusCode::CONFLICT);
+        assert_eq!(replay.headers()["idempotent-replayed"], "true");
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_bounds() {
+        let fingerprint =
+            RequestFingerprint { method: "POST".to_string(), path: "/".to_string(), body_sha256: String::new() };
+        let resp =
+            |size: usize| StoredResponse { status: 200, headers: Vec::new(), body: vec![0; size], body_omitted: false };
+        let ttl = Duration::from_secs(60);
+
+        // 按数量淘汰
+        let store = MemoryIdempotencyStore::with_capacity(2, usize::MAX);
+        for key in ["a", "b", "c"] {
+            store.begin(key, &fingerprint, ttl).await.unwrap();
+        }
+        assert_eq!(store.begin("a", &fingerprint, ttl).await.unwrap(), IdempotencyState::Started);
+
+        // 按大小淘汰
+        let store = MemoryIdempotencyStore::with_capacity(100, 1000);
+        store.complete("a", &fingerprint, &resp(600), ttl).await.unwrap();
+        store.complete("b", &fingerprint, &resp(600), ttl).await.unwrap();
+        assert_eq!(store.begin("a", &fingerprint, ttl).await.unwrap(), IdempotencyState::Started);
+        assert!(matches!(store.begin("b", &fingerprint, ttl).await.unwrap(), IdempotencyState::Completed(..)));
+        assert!(store.entries.lock().unwrap().size <= 1000);
+    }
+
+    #[tokio::test]
+    async fn test_forged_principal() {
+        let counter = Arc::new(AtomicU32::new(0));
+        let handler_counter = counter.clone();
+        let signer = PrincipalSigner::from_secret("s3cret");
+        let state = Idempotency::new(IdempotencyConfig::default(), Arc::new(MemoryIdempotencyStore::new()))
+            .with_signer(Some(signer.clone()));
+        let router = Router::new()
+            .route(
+                "/pay",
+                post(move || async move { format!("order-{}", handler_counter.fetch_add(1, Ordering::SeqCst) + 1) }),
+            )
+            .layer(axum::middleware::from_fn_with_state(state, idempotency));
+        let principal = EndUserPrincipal { id: 1, account: "a".to_string(), tenant_id: "t".to_string() };
+        let body = |resp: Response| async move {
+            String::from_utf8(axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+        };
+
+        let mut req = Request::post("/pay")
+            .header("idempotency-key", "k1")
+            .body(Body::empty())
+            .unwrap();
+        req.headers_mut().extend(signer.headers(&principal));
+        assert_eq!(body(router.clone().oneshot(req).await.unwrap()).await, "order-1");
+
+        // 伪造的身份不能重放他人的响应
+        let forged = serde_json::to_string(&principal).unwrap();
+        let req = Request::post("/pay")
+            .header("idempotency-key", "k1")
+            .header(CUSTOM_PRINCIPAL_HEADER, forged)
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert!(!resp.headers().contains_key("idempotent-replayed"));
+        assert_eq!(body(resp).await, "order-2");
+    }
+}
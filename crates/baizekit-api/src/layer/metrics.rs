@@ -0,0 +1,176 @@
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::Method;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use baizekit_app::metrics::prometheus::{
+    HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, exponential_buckets, histogram_opts, opts,
+};
+use baizekit_app::metrics::{gather_text, register};
+
+/// 未匹配到路由的请求统一使用该标签，避免按原始路径产生大量时间序列
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// HTTP RED 指标，按方法、路由模板和状态码统计
+pub struct HttpMetrics {
+    requests: IntCounterVec,
+    duration: HistogramVec,
+    in_flight: IntGaugeVec,
+}
+
+static HTTP_METRICS: LazyLock<HttpMetrics> = LazyLock::new(HttpMetrics::new);
+
+impl HttpMetrics {
+    fn new() -> Self {
+        let requests = IntCounterVec::new(
+            opts!("http_server_requests_total", "Total number of HTTP requests"),
+            &["method", "route", "status"],
+        )
+        .expect("invalid http_server_requests_total metric");
+        let duration = HistogramVec::new(
+            histogram_opts!(
+                "http_server_request_duration_seconds",
+                "HTTP request latency in seconds",
+                exponential_buckets(0.005, 2.0, 12).expect("invalid histogram buckets")
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("invalid http_server_request_duration_seconds metric");
+        let in_flight = IntGaugeVec::new(
+            opts!("http_server_requests_in_flight", "Number of HTTP requests in flight"),
+            &["method", "route"],
+        )
+        .expect("invalid http_server_requests_in_flight metric");
+
+        register(requests.clone());
+        register(duration.clone());
+        register(in_flight.clone());
+
+        Self { requests, duration, in_flight }
+    }
+
+    /// 全局 HTTP 指标，首次访问时注册到 [`baizekit_app::metrics::registry`]
+    pub fn global() -> &'static HttpMetrics {
+        &HTTP_METRICS
+    }
+}
+
+/// 非标准方法统一使用 `OTHER` 标签，避免客户端构造任意方法产生大量时间序列
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::DELETE => "DELETE",
+        Method::PATCH => "PATCH",
+        Method::HEAD => "HEAD",
+        Method::OPTIONS => "OPTIONS",
+        Method::CONNECT => "CONNECT",
+        Method::TRACE => "TRACE",
+        _ => "OTHER",
+    }
+}
+
+/// 并发数计数，请求完成或被取消(客户端断开、超时)时减一
+struct InFlightGuard(IntGauge);
+
+impl InFlightGuard {
+    fn new(gauge: IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// 记录请求数、延迟和并发数的中间件，需通过 `Router::layer` 挂载以获取路由模板
+pub async fn http_metrics(req: Request, next: Next) -> Response {
+    let metrics = HttpMetrics::global();
+    let method = method_label(req.method());
+    let route = match req.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => UNMATCHED_ROUTE.to_string(),
+    };
+
+    let in_flight = InFlightGuard::new(metrics.in_flight.with_label_values(&[method, &route]));
+    let start = Instant::now();
+
+    let resp = next.run(req).await;
+
+    drop(in_flight);
+    let status = resp.status().as_u16().to_string();
+    let labels = [method, route.as_str(), status.as_str()];
+    metrics.requests.with_label_values(&labels).inc();
+    metrics
+        .duration
+        .with_label_values(&labels)
+        .observe(start.elapsed().as_secs_f64());
+
+    resp
+}
+
+/// `/metrics` 端点，以 Prometheus 文本格式导出全局注册表
+pub async fn metrics_handler() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], gather_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_http_metrics() {
+        let router = Router::new()
+            .route("/users/{id}", get(|| async { "OK" }))
+            .route("/metrics", get(metrics_handler))
+            .layer(axum::middleware::from_fn(http_metrics));
+
+        let req = Request::get("/users/42").body(Body::empty()).unwrap();
+        router.clone().oneshot(req).await.unwrap();
+
+        let req = Request::get("/metrics").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains(r#"http_server_requests_total{method="GET",route="/users/{id}",status="200"} 1"#));
+        assert!(body.contains("http_server_request_duration_seconds_bucket"));
+        assert!(body.contains(r#"http_server_requests_in_flight{method="GET",route="/metrics"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn test_method_label_and_cancelled_request() {
+        let router = Router::new()
+            .route("/purge", axum::routing::any(|| async { "OK" }))
+            .route("/pending", get(std::future::pending::<()>))
+            .layer(axum::middleware::from_fn(http_metrics));
+
+        let req = Request::builder()
+            .method(Method::from_bytes(b"PURGE").unwrap())
+            .uri("/purge")
+            .body(Body::empty())
+            .unwrap();
+        router.clone().oneshot(req).await.unwrap();
+        let requests = &HttpMetrics::global().requests;
+        assert_eq!(requests.with_label_values(&["OTHER", "/purge", "200"]).get(), 1);
+
+        // 请求被取消时并发数同样减一
+        let in_flight = HttpMetrics::global().in_flight.with_label_values(&["GET", "/pending"]);
+        let req = Request::get("/pending").body(Body::empty()).unwrap();
+        let pending = tokio::time::timeout(std::time::Duration::from_millis(20), router.oneshot(req));
+        assert!(pending.await.is_err());
+        assert_eq!(in_flight.get(), 0);
+    }
+}
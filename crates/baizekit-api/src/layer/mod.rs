@@ -0,0 +1,29 @@
+mod audit;
+mod cache;
+mod cors;
+mod csrf;
+mod deprecation;
+mod idempotency;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod panic;
+mod rate_limit;
+mod reply;
+mod security;
+mod timeout;
+mod trace;
+
+pub use audit::*;
+pub use cache::*;
+pub use cors::*;
+pub use csrf::*;
+pub use deprecation::*;
+pub use idempotency::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
+pub use panic::*;
+pub use rate_limit::*;
+pub use reply::*;
+pub use security::*;
+pub use timeout::*;
+pub use trace::*;
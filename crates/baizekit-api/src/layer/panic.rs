@@ -0,0 +1,186 @@
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::future::{Future, poll_fn};
+use std::panic::AssertUnwindSafe;
+use std::pin::pin;
+use std::sync::{Arc, Once};
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use crate::response::Reply;
+
+/// panic 捕获配置，读取自 `axum.{label}.catch_panic`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CatchPanicConfig {
+    /// 是否捕获处理函数中的 panic 并返回 500
+    pub enabled: bool,
+    /// 是否在日志中输出 panic 发生处的调用栈
+    pub backtrace: bool,
+}
+
+impl Default for CatchPanicConfig {
+    fn default() -> Self {
+        Self { enabled: true, backtrace: true }
+    }
+}
+
+thread_local! {
+    /// 当前线程是否正在执行 [`catch_panic`] 包裹的请求，仅此时记录调用栈
+    static CAPTURE_BACKTRACE: Cell<bool> = const { Cell::new(false) };
+    /// 当前线程最近一次 panic 的调用栈，由 panic hook 写入
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// 安装 panic hook 记录调用栈，保留原有的 hook
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CAPTURE_BACKTRACE.get() {
+                PANIC_BACKTRACE.with(|bt| *bt.borrow_mut() = Some(Backtrace::force_capture()));
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// 每次 poll 期间开启当前线程的调用栈记录，任务在线程间迁移时同样生效
+async fn capture_backtrace<F: Future>(future: F) -> F::Output {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            CAPTURE_BACKTRACE.set(self.0);
+        }
+    }
+
+    let mut future = pin!(future);
+    poll_fn(|cx| {
+        let _reset = Reset(CAPTURE_BACKTRACE.replace(true));
+        future.as_mut().poll(cx)
+    })
+    .await
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// panic 捕获中间件状态
+#[derive(Clone)]
+pub struct CatchPanic {
+    config: Arc<CatchPanicConfig>,
+}
+
+impl CatchPanic {
+    pub fn new(config: CatchPanicConfig) -> Self {
+        if config.backtrace {
+            install_panic_hook();
+        }
+        Self { config: Arc::new(config) }
+    }
+}
+
+/// panic 捕获中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 处理函数 panic 时输出带请求 ID 和调用栈的错误日志，并返回 `Reply` 格式的 500 响应。
+pub async fn catch_panic(State(catch_panic): State<CatchPanic>, req: Request, next: Next) -> Response {
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    let result = match catch_panic.config.backtrace {
+        true => capture_backtrace(AssertUnwindSafe(next.run(req)).catch_unwind()).await,
+        false => AssertUnwindSafe(next.run(req)).catch_unwind().await,
+    };
+    let payload = match result {
+        Ok(resp) => return resp,
+        Err(payload) => payload,
+    };
+
+    let message = panic_message(payload.as_ref());
+    let backtrace = PANIC_BACKTRACE.with(|bt| bt.borrow_mut().take());
+    match backtrace.filter(|_| catch_panic.config.backtrace) {
+        Some(backtrace) => {
+            tracing::error!(?request_id, %method, path, "handler panicked: {}\n{}", message, backtrace)
+        }
+        None => tracing::error!(?request_id, %method, path, "handler panicked: {}", message),
+    }
+
+    let status = StatusCode::INTERNAL_SERVER_ERROR;
+    let reply = Reply::<()> { code: status.as_u16() as i32, message: "InternalServerError".to_string(), data: None };
+    (status, reply).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn boom() -> &'static str {
+        panic!("boom")
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        let router = Router::new()
+            .route("/ok", get(|| async { "OK" }))
+            .route("/panic", get(boom))
+            .layer(axum::middleware::from_fn_with_state(CatchPanic::new(CatchPanicConfig::default()), catch_panic));
+
+        let resp = router
+            .clone()
+            .oneshot(Request::get("/ok").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = router
+            .oneshot(Request::get("/panic").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let reply: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["code"], 500);
+        assert_eq!(reply["message"], "InternalServerError");
+    }
+
+    #[test]
+    fn test_backtrace_only_inside_catch_panic() {
+        CatchPanic::new(CatchPanicConfig::default());
+
+        let _ = std::panic::catch_unwind(|| panic!("outside"));
+        assert!(PANIC_BACKTRACE.with(|bt| bt.borrow_mut().take()).is_none());
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let result = runtime.block_on(capture_backtrace(AssertUnwindSafe(boom()).catch_unwind()));
+        assert!(result.is_err());
+        assert!(PANIC_BACKTRACE.with(|bt| bt.borrow_mut().take()).is_some());
+        assert!(!CAPTURE_BACKTRACE.get());
+    }
+}
@@ -0,0 +1,508 @@
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use baizekit_app::anyhow::{Result, bail};
+use baizekit_app::async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::extract::{PrincipalSigner, request_principal};
+use crate::response::Reply;
+
+/// 限流维度
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// 按客户端 IP
+    #[default]
+    Ip,
+    /// 按登录主体 id，未登录时退化为 IP
+    Principal,
+    /// 按路由(方法 + 路由模板)
+    Route,
+}
+
+/// 令牌桶规则
+///
+/// 按 IP 限流(包括按主体限流时退化为 IP)但取不到客户端地址时不限流，例如 unix socket 监听器且未信任转发头。
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RateLimitRule {
+    /// 限流维度
+    #[serde(default)]
+    pub key: RateLimitKey,
+    /// 桶容量，即允许的突发请求数，必须大于 0
+    pub capacity: u64,
+    /// 每秒补充的令牌数，不能为负数，为 0 时令牌耗尽后不再补充
+    pub refill_per_second: f64,
+}
+
+impl RateLimitRule {
+    fn validate(&self, rule_id: &str) -> Result<()> {
+        if self.capacity == 0 {
+            bail!("rate limit rule '{}' requires capacity greater than 0", rule_id);
+        }
+        if !self.refill_per_second.is_finite() || self.refill_per_second < 0.0 {
+            bail!("rate limit rule '{}' has invalid refill_per_second {}", rule_id, self.refill_per_second);
+        }
+        Ok(())
+    }
+}
+
+/// 按路由前缀配置的限流规则
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RateLimitRoute {
+    /// 路由前缀，例如 `/api/v1/login`
+    pub prefix: String,
+    #[serde(flatten)]
+    pub rule: RateLimitRule,
+}
+
+/// 限流配置，读取自 `axum.{label}.rate_limit`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// 全局默认规则，未配置时仅对 `routes` 中匹配的路由限流
+    pub default: Option<RateLimitRule>,
+    /// 按路由前缀配置的规则，最长前缀优先
+    pub routes: Vec<RateLimitRoute>,
+    /// 是否信任 `x-forwarded-for`/`x-real-ip` 中的客户端 IP，仅在反向代理之后开启
+    pub trust_forwarded: bool,
+}
+
+impl RateLimitConfig {
+    /// 匹配请求路径对应的规则，返回 (规则标识, 规则)
+    fn match_rule(&self, path: &str) -> Option<(&str, &RateLimitRule)> {
+        self.routes
+            .iter()
+            .filter(|r| path_has_prefix(path, &r.prefix))
+            .max_by_key(|r| r.prefix.len())
+            .map(|r| (r.prefix.as_str(), &r.rule))
+            .or_else(|| self.default.as_ref().map(|rule| ("*", rule)))
+    }
+}
+
+/// 按路径段匹配前缀，`/api` 匹配 `/api` 和 `/api/users`，不匹配 `/apis`
+pub(crate) fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+/// 令牌获取结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+/// 令牌桶存储
+#[async_trait]
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// 从 `key` 对应的令牌桶中获取一个令牌
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision>;
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// 进程内令牌桶存储，仅适用于单实例部署
+///
+/// 令牌桶数量超过上限时淘汰最久未访问的令牌桶，被淘汰的 key 再次访问时重新从满桶开始。
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<lru::LruCache<String, Bucket>>,
+}
+
+impl Default for MemoryRateLimitStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryRateLimitStore {
+    /// 默认的令牌桶数量上限
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// `capacity` 为令牌桶数量上限
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self { buckets: Mutex::new(lru::LruCache::new(capacity)) }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision> {
+        let now = Instant::now();
+        let capacity = rule.capacity as f64;
+        let mut buckets = self.buckets.lock().expect("Failed to lock rate limit buckets");
+
+        let bucket = buckets.get_or_insert_mut(key.to_string(), || Bucket { tokens: capacity, updated_at: now });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rule.refill_per_second).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(RateLimitDecision::Allowed);
+        }
+
+        let retry_after =
+            Duration::try_from_secs_f64((1.0 - bucket.tokens) / rule.refill_per_second).unwrap_or(Duration::MAX);
+        Ok(RateLimitDecision::Limited { retry_after })
+    }
+}
+
+/// 基于 Redis 的令牌桶存储，适用于多实例部署
+#[cfg(feature = "redis")]
+pub struct RedisRateLimitStore {
+    client: baizekit_redis::redis::Client,
+    conn: tokio::sync::OnceCell<baizekit_redis::redis::aio::MultiplexedConnection>,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisRateLimitStore {
+    const SCRIPT: &'static str = r#"
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local data = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(data[1]) or capacity
+local ts = tonumber(data[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) / 1000 * rate)
+local wait = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+elseif rate > 0 then
+    wait = math.ceil((1 - tokens) / rate * 1000)
+else
+    wait = -1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+if rate > 0 then
+    redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / rate * 1000) + 1000)
+end
+return wait
+"#;
+
+    pub fn new(client: baizekit_redis::redis::Client) -> Self {
+        Self { client, conn: tokio::sync::OnceCell::new(), prefix: "baizekit:rate_limit".to_string() }
+    }
+
+    /// 设置 Redis key 前缀，默认 `baizekit:rate_limit`
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn acquire(&self, key: &str, rule: &RateLimitRule) -> Result<RateLimitDecision> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| self.client.get_multiplexed_async_connection())
+            .await?;
+        let wait: i64 = baizekit_redis::redis::Script::new(Self::SCRIPT)
+            .key(format!("{}:{}", self.prefix, key))
+            .arg(rule.capacity)
+            .arg(rule.refill_per_second)
+            .invoke_async(&mut conn.clone())
+            .await?;
+
+        Ok(match wait {
+            0 => RateLimitDecision::Allowed,
+            w if w < 0 => RateLimitDecision::Limited { retry_after: Duration::MAX },
+            w => RateLimitDecision::Limited { retry_after: Duration::from_millis(w as u64) },
+        })
+    }
+}
+
+/// 限流中间件状态
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    store: Arc<dyn RateLimitStore>,
+    signer: Option<PrincipalSigner>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Result<Self> {
+        if let Some(rule) = &config.default {
+            rule.validate("*")?;
+        }
+        for route in &config.routes {
+            route.rule.validate(&route.prefix)?;
+        }
+        Ok(Self { config: Arc::new(config), store, signer: None })
+    }
+
+    /// 启用身份签名时，签名无效的身份按匿名处理，退化为按 IP 限流
+    pub fn with_signer(mut self, signer: Option<PrincipalSigner>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    fn client_ip(&self, req: &Request) -> Option<String> {
+        if self.config.trust_forwarded {
+            let forwarded = forwarded_ip(req.headers());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    }
+
+    /// 令牌桶 key，需要按 IP 限流但取不到客户端地址时返回 `None`
+    fn bucket_key(&self, req: &Request, rule_id: &str, rule: &RateLimitRule) -> Option<String> {
+        let ip = || self.client_ip(req).map(|ip| format!("ip:{}", ip));
+
+        let subject = match rule.key {
+            RateLimitKey::Ip => ip()?,
+            RateLimitKey::Principal => match request_principal(req.headers(), self.signer.as_ref()) {
+                Some(principal) => principal.id,
+                None => ip()?,
+            },
+            RateLimitKey::Route => {
+                let route = req
+                    .extensions()
+                    .get::<MatchedPath>()
+                    .map(|p| p.as_str())
+                    .unwrap_or(req.uri().path());
+                format!("route:{} {}", req.method(), route)
+            }
+        };
+
+        Some(format!("{}|{}", rule_id, subject))
+    }
+}
+
+fn forwarded_ip(headers: &HeaderMap) -> Option<String> {
+    if let Some(value) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok())
+        && let Some(ip) = value.split(',').next().map(str::trim).filter(|ip| !ip.is_empty())
+    {
+        return Some(ip.to_string());
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .map(|ip| ip.trim().to_string())
+}
+
+/// 限流中间件，配合 `axum::middleware::from_fn_with_state` 使用
+pub async fn rate_limit(State(limiter): State<RateLimiter>, req: Request, next: Next) -> Response {
+    let Some((rule_id, rule)) = limiter.config.match_rule(req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let Some(key) = limiter.bucket_key(&req, rule_id, rule) else {
+        return next.run(req).await;
+    };
+    match limiter.store.acquire(&key, rule).await {
+        Ok(RateLimitDecision::Allowed) => next.run(req).await,
+        Ok(RateLimitDecision::Limited { retry_after }) => {
+            tracing::warn!(key, "rate limited");
+            too_many_requests(retry_after)
+        }
+        Err(err) => {
+            // 存储不可用时放行，避免限流组件故障导致整体不可用
+            tracing::error!(key, "rate limit store error: {:?}", err);
+            next.run(req).await
+        }
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let reply = Reply::<()> { code: 429, message: "Too Many Requests".to_string(), data: None };
+    let mut resp = (StatusCode::TOO_MANY_REQUESTS, Json(reply)).into_response();
+
+    if retry_after != Duration::MAX {
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        resp.headers_mut().insert(RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn rule(key: RateLimitKey, capacity: u64) -> RateLimitRule {
+        RateLimitRule { key, capacity, refill_per_second: 1.0 }
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        let store = MemoryRateLimitStore::new();
+        let rule = rule(RateLimitKey::Ip, 2);
+
+        assert_eq!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Allowed);
+        assert_eq!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Allowed);
+        assert!(matches!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Limited { .. }));
+        assert_eq!(store.acquire("b", &rule).await.unwrap(), RateLimitDecision::Allowed);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_lru() {
+        let store = MemoryRateLimitStore::with_capacity(1);
+        let rule = RateLimitRule { key: RateLimitKey::Ip, capacity: 1, refill_per_second: 0.0 };
+
+        assert_eq!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Allowed);
+        assert_eq!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Limited { retry_after: Duration::MAX });
+        // 超出上限时淘汰最久未访问的令牌桶
+        assert_eq!(store.acquire("b", &rule).await.unwrap(), RateLimitDecision::Allowed);
+        assert_eq!(store.buckets.lock().unwrap().len(), 1);
+        assert_eq!(store.acquire("a", &rule).await.unwrap(), RateLimitDecision::Allowed);
+    }
+
+    #[test]
+    fn test_match_rule() {
+        let config = RateLimitConfig {
+            default: Some(rule(RateLimitKey::Ip, 100)),
+            routes: vec![
+                RateLimitRoute { prefix: "/api".to_string(), rule: rule(RateLimitKey::Route, 10) },
+                RateLimitRoute { prefix: "/api/login".to_string(), rule: rule(RateLimitKey::Ip, 1) },
+            ],
+            trust_forwarded: false,
+        };
+
+        assert_eq!(config.match_rule("/api/login/sms").map(|(id, r)| (id, r.capacity)), Some(("/api/login", 1)));
+        assert_eq!(config.match_rule("/api/users").map(|(id, r)| (id, r.capacity)), Some(("/api", 10)));
+        assert_eq!(config.match_rule("/health").map(|(id, r)| (id, r.capacity)), Some(("*", 100)));
+        // 前缀按路径段匹配
+        assert_eq!(config.match_rule("/api/loginx").map(|(id, r)| (id, r.capacity)), Some(("/api", 10)));
+        assert_eq!(config.match_rule("/apis").map(|(id, r)| (id, r.capacity)), Some(("*", 100)));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_returns_429() {
+        let config = RateLimitConfig {
+            routes: vec![RateLimitRoute { prefix: "/limited".to_string(), rule: rule(RateLimitKey::Principal, 1) }],
+            trust_forwarded: true,
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config, Arc::new(MemoryRateLimitStore::new())).unwrap();
+        let router = Router::new()
+            .route("/limited", get(|| async { "OK" }))
+            .route("/free", get(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit));
+
+        let request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .header("x-forwarded-for", "10.0.0.1")
+                .body(Body::empty())
+        };
+
+        let resp = router.clone().oneshot(request("/limited").unwrap()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = router.clone().oneshot(request("/limited").unwrap()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()[RETRY_AFTER], "1");
+
+        let resp = router.clone().oneshot(request("/free").unwrap()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_forged_principal_falls_back_to_ip() {
+        let config = RateLimitConfig {
+            routes: vec![RateLimitRoute { prefix: "/limited".to_string(), rule: rule(RateLimitKey::Principal, 1) }],
+            trust_forwarded: true,
+            ..Default::default()
+        };
+        let signer = PrincipalSigner::from_secret("s3cret");
+        let limiter = RateLimiter::new(config, Arc::new(MemoryRateLimitStore::new()))
+            .unwrap()
+            .with_signer(Some(signer.clone()));
+        let router = Router::new()
+            .route("/limited", get(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit));
+
+        let request = |headers: HeaderMap| {
+            let mut req = Request::builder()
+                .uri("/limited")
+                .header("x-forwarded-for", "10.0.0.1")
+                .body(Body::empty())
+                .unwrap();
+            req.headers_mut().extend(headers);
+            req
+        };
+        let forged = |id: i32| {
+            let mut headers = HeaderMap::new();
+            let principal = format!(r#"{{"id":{},"account":"a","tenant_id":"t"}}"#, id);
+            headers.insert(crate::extract::CUSTOM_PRINCIPAL_HEADER, HeaderValue::from_str(&principal).unwrap());
+            headers
+        };
+
+        // 伪造不同身份不能绕过限流，均按 IP 计数
+        let resp = router.clone().oneshot(request(forged(1))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = router.clone().oneshot(request(forged(2))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let principal =
+            crate::extract::EndUserPrincipal { id: 3, account: "a".to_string(), tenant_id: "t".to_string() };
+        let resp = router.oneshot(request(signer.headers(&principal))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_invalid_rule() {
+        let limiter = |rule: RateLimitRule| {
+            let config = RateLimitConfig { default: Some(rule), ..Default::default() };
+            RateLimiter::new(config, Arc::new(MemoryRateLimitStore::new()))
+        };
+
+        assert!(limiter(rule(RateLimitKey::Ip, 0)).is_err());
+        for refill_per_second in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(limiter(RateLimitRule { refill_per_second, ..rule(RateLimitKey::Ip, 1) }).is_err());
+        }
+        assert!(limiter(RateLimitRule { refill_per_second: 0.0, ..rule(RateLimitKey::Ip, 1) }).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_ip_is_not_limited() {
+        let config = RateLimitConfig { default: Some(rule(RateLimitKey::Ip, 1)), ..Default::default() };
+        let limiter = RateLimiter::new(config, Arc::new(MemoryRateLimitStore::new())).unwrap();
+        let router = Router::new()
+            .route("/", get(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(limiter, rate_limit));
+
+        // 没有 ConnectInfo 的请求(例如 unix socket)不共用同一个令牌桶
+        for _ in 0..3 {
+            let resp = router
+                .clone()
+                .oneshot(Request::get("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use axum::http::{HeaderMap, HeaderValue, Method, Uri};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::response::{RenderedReply, ReplyMarker};
+
+tokio::task_local! {
+    /// 当前请求的 `Reply` 是否需要保留序列化结果供重新编码
+    static RENDER_REPLY: bool;
+}
+
+/// 当前请求是否需要生成 [`RenderedReply`]
+pub(crate) fn render_requested() -> bool {
+    RENDER_REPLY.try_with(|render| *render).unwrap_or(false)
+}
+
+/// 响应包装方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyEnvelope {
+    /// `{ code, message, data }`
+    #[default]
+    Enveloped,
+    /// 成功时仅返回 `data`，失败时仍返回完整的 `Reply`
+    Raw,
+}
+
+/// 响应序列化格式，按请求头 `Accept` 协商
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyFormat {
+    #[default]
+    Json,
+    /// 需启用 `msgpack` feature
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    /// 需启用 `cbor` feature
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl ReplyFormat {
+    /// 按 `Accept` 的 q 值选择支持的格式，q 值相同时取先出现的，`q=0` 表示不接受，未匹配时使用 JSON
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(ACCEPT).and_then(|v| v.to_str().ok()) else {
+            return Self::Json;
+        };
+
+        let mut best: Option<(Self, f32)> = None;
+        for item in accept.split(',') {
+            let mut params = item.split(';');
+            let Some(format) = params.next().and_then(|mime| Self::from_mime(mime.trim())) else {
+                continue;
+            };
+            let q = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if q > 0.0 && best.is_none_or(|(_, best_q)| q > best_q) {
+                best = Some((format, q));
+            }
+        }
+        best.map(|(format, _)| format).unwrap_or_default()
+    }
+
+    fn from_mime(mime: &str) -> Option<Self> {
+        match mime {
+            "application/json" | "*/*" => Some(Self::Json),
+            #[cfg(feature = "msgpack")]
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(Self::MsgPack),
+            #[cfg(feature = "cbor")]
+            "application/cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).map_err(|e| e.to_string())?;
+                Ok(buf)
+            }
+        }
+    }
+}
+
+/// 响应配置，读取自 `axum.{label}.reply`
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplyConfig {
+    /// 全局响应包装方式，可通过 [`raw_reply`] 按路由覆盖
+    pub envelope: ReplyEnvelope,
+    /// 是否在 `Reply` 中添加 `request_id` 字段，取自 `x-request-id` 请求头
+    pub request_id: bool,
+    /// 是否在 `Reply` 中添加 `timestamp` 字段(毫秒时间戳)
+    pub timestamp: bool,
+}
+
+/// 生成响应时可见的请求信息
+pub struct ReplyContext {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+}
+
+/// 向每个 `Reply` 添加额外字段，例如 `trace_id`
+pub trait ReplyHook: Send + Sync + 'static {
+    fn extend(&self, ctx: &ReplyContext, fields: &mut Map<String, Value>);
+}
+
+struct RequestIdHook;
+
+impl ReplyHook for RequestIdHook {
+    fn extend(&self, ctx: &ReplyContext, fields: &mut Map<String, Value>) {
+        if let Some(id) = ctx.headers.get("x-request-id").and_then(|v| v.to_str().ok()) {
+            fields.insert("request_id".to_string(), Value::from(id));
+        }
+    }
+}
+
+struct TimestampHook;
+
+impl ReplyHook for TimestampHook {
+    fn extend(&self, _: &ReplyContext, fields: &mut Map<String, Value>) {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        fields.insert("timestamp".to_string(), Value::from(millis));
+    }
+}
+
+/// 响应包装与内容协商
+#[derive(Clone)]
+pub struct ReplyRenderer {
+    envelope: ReplyEnvelope,
+    hooks: Arc<Vec<Arc<dyn ReplyHook>>>,
+}
+
+impl ReplyRenderer {
+    pub fn new(config: &ReplyConfig, custom_hooks: Vec<Arc<dyn ReplyHook>>) -> Self {
+        let mut hooks: Vec<Arc<dyn ReplyHook>> = Vec::new();
+        if config.request_id {
+            hooks.push(Arc::new(RequestIdHook));
+        }
+        if config.timestamp {
+            hooks.push(Arc::new(TimestampHook));
+        }
+        hooks.extend(custom_hooks);
+
+        Self { envelope: config.envelope, hooks: Arc::new(hooks) }
+    }
+
+    fn render(&self, reply: RenderedReply, envelope: ReplyEnvelope, ctx: Option<&ReplyContext>) -> Value {
+        let RenderedReply(reply) = reply;
+        if envelope == ReplyEnvelope::Raw && reply.code == 0 {
+            return reply.data.unwrap_or(Value::Null);
+        }
+
+        let mut fields = Map::new();
+        fields.insert("code".to_string(), Value::from(reply.code));
+        fields.insert("message".to_string(), Value::from(reply.message));
+        if let Some(data) = reply.data {
+            fields.insert("data".to_string(), data);
+        }
+        if let Some(ctx) = ctx {
+            self.hooks.iter().for_each(|hook| hook.extend(ctx, &mut fields));
+        }
+        Value::Object(fields)
+    }
+}
+
+/// 将路由的响应改为不包装，配合 `route_layer` 使用
+///
+/// ```ignore
+/// Router::new().route("/raw", get(handler)).route_layer(axum::middleware::from_fn(raw_reply))
+/// ```
+pub async fn raw_reply(req: Request, next: Next) -> Response {
+    let mut resp = RENDER_REPLY.scope(true, next.run(req)).await;
+    resp.extensions_mut().insert(ReplyEnvelope::Raw);
+    resp
+}
+
+/// 按配置重新编码 `Reply` 响应：包装方式、`Accept` 协商格式以及附加字段
+pub async fn reply_negotiation(State(renderer): State<ReplyRenderer>, req: Request, next: Next) -> Response {
+    let format = ReplyFormat::from_accept(req.headers());
+    let ctx = match renderer.hooks.is_empty() {
+        true => None,
+        false => {
+            Some(ReplyContext { method: req.method().clone(), uri: req.uri().clone(), headers: req.headers().clone() })
+        }
+    };
+
+    // 默认 JSON 包装且无附加字段时，`Reply` 直接序列化，不生成中间结果
+    let render = format != ReplyFormat::Json || renderer.envelope == ReplyEnvelope::Raw || ctx.is_some();
+    let mut resp = RENDER_REPLY.scope(render, next.run(req)).await;
+    // 启用其他格式时，`Reply` 响应的编码取决于 `Accept`
+    if cfg!(any(feature = "msgpack", feature = "cbor")) && resp.extensions().get::<ReplyMarker>().is_some() {
+        resp.headers_mut().append(VARY, HeaderValue::from_static("accept"));
+    }
+    let envelope = resp.extensions().get::<ReplyEnvelope>().copied().unwrap_or(renderer.envelope);
+    // 默认 JSON 包装且无附加字段时，沿用原始响应体
+    if format == ReplyFormat::Json && envelope == ReplyEnvelope::Enveloped && ctx.is_none() {
+        return resp;
+    }
+    let Some(reply) = resp.extensions_mut().remove::<RenderedReply>() else {
+        return resp;
+    };
+
+    let value = renderer.render(reply, envelope, ctx.as_ref());
+    let body = match format.encode(&value) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("encode reply failed: {}", err);
+            return resp;
+        }
+    };
+
+    let headers = resp.headers_mut();
+    headers.remove(CONTENT_LENGTH);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+    *resp.body_mut() = Body::from(body);
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::response::ApiOK;
+
+    async fn call(router: &Router, uri: &str, accept: &str) -> (String, Vec<u8>) {
+        let req = Request::get(uri)
+            .header(ACCEPT, accept)
+            .header("x-request-id", "req-1")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.clone().oneshot(req).await.unwrap();
+        let content_type = resp.headers()[CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (content_type, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_envelope_and_hooks() {
+        let config = ReplyConfig { request_id: true, timestamp: true, ..Default::default() };
+        let renderer = ReplyRenderer::new(&config, Vec::new());
+        let router = Router::new()
+            .route("/user", get(|| async { ApiOK::with_data("alice") }))
+            .merge(
+                Router::new()
+                    .route("/raw", get(|| async { ApiOK::with_data("bob") }))
+                    .route_layer(axum::middleware::from_fn(raw_reply)),
+            )
+            .layer(axum::middleware::from_fn_with_state(renderer, reply_negotiation));
+
+        let (content_type, body) = call(&router, "/user", "application/json").await;
+        assert_eq!(content_type, "application/json");
+        let reply: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["data"], "alice");
+        assert_eq!(reply["request_id"], "req-1");
+        assert!(reply["timestamp"].as_u64().unwrap() > 0);
+
+        let (_, body) = call(&router, "/raw", "*/*").await;
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap(), Value::from("bob"));
+    }
+
+    #[tokio::test]
+    async fn test_default_json_keeps_field_order() {
+        #[derive(Serialize)]
+        struct User {
+            zeta: i32,
+            alpha: i32,
+        }
+
+        let renderer = ReplyRenderer::new(&ReplyConfig::default(), Vec::new());
+        let router = Router::new()
+            .route("/user", get(|| async { ApiOK::with_data(User { zeta: 1, alpha: 2 }) }))
+            .layer(axum::middleware::from_fn_with_state(renderer, reply_negotiation));
+
+        let req = Request::get("/user").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert!(resp.extensions().get::<RenderedReply>().is_none());
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, r#"{"code":0,"message":"OK","data":{"zeta":1,"alpha":2}}"#);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[tokio::test]
+    async fn test_msgpack() {
+        let renderer = ReplyRenderer::new(&ReplyConfig::default(), Vec::new());
+        let router = Router::new()
+            .route("/user", get(|| async { ApiOK::with_data("alice") }))
+            .layer(axum::middleware::from_fn_with_state(renderer, reply_negotiation));
+
+        let (content_type, body) = call(&router, "/user", "application/msgpack, application/json;q=0.9").await;
+        assert_eq!(content_type, "application/msgpack");
+        let reply: crate::response::Reply<String> = rmp_serde::from_slice(&body).unwrap();
+        assert_eq!(reply.data.as_deref(), Some("alice"));
+
+        // 按 q 值而非出现顺序选择
+        let (content_type, _) = call(&router, "/user", "application/json, application/msgpack;q=0.5").await;
+        assert_eq!(content_type, "application/json");
+        let (content_type, _) = call(&router, "/user", "application/json;q=0.5, application/msgpack").await;
+        assert_eq!(content_type, "application/msgpack");
+        let (content_type, _) = call(&router, "/user", "application/msgpack;q=0, */*;q=0.1").await;
+        assert_eq!(content_type, "application/json");
+
+        let req = Request::get("/user").body(Body::empty()).unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        assert_eq!(resp.headers()[VARY], "accept");
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn test_cbor() {
+        let renderer = ReplyRenderer::new(&ReplyConfig::default(), Vec::new());
+        let router = Router::new()
+            .route("/user", get(|| async { ApiOK::with_data("alice") }))
+            .layer(axum::middleware::from_fn_with_state(renderer, reply_negotiation));
+
+        let (content_type, body) = call(&router, "/user", "application/cbor").await;
+        assert_eq!(content_type, "application/cbor");
+        let reply: crate::response::Reply<String> = ciborium::from_reader(body.as_slice()).unwrap();
+        assert_eq!(reply.data.as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use baizekit_app::anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::layer::rate_limit::path_has_prefix;
+
+/// 安全响应头配置，读取自 `axum.{label}.security_headers`
+///
+/// 值为空字符串时不添加该响应头；处理函数已设置的响应头不会被覆盖。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SecurityHeadersConfig {
+    /// `Strict-Transport-Security`，仅在 HTTPS 下生效
+    pub hsts: String,
+    /// `X-Content-Type-Options`
+    pub content_type_options: String,
+    /// `X-Frame-Options`
+    pub frame_options: String,
+    /// `Content-Security-Policy`
+    pub content_security_policy: String,
+    /// `Referrer-Policy`
+    pub referrer_policy: String,
+    /// 按路径前缀覆盖响应头，键为响应头名称，值为空字符串时移除该响应头；匹配最长的前缀
+    pub overrides: Vec<SecurityHeadersOverride>,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            hsts: "max-age=31536000; includeSubDomains".to_string(),
+            content_type_options: "nosniff".to_string(),
+            frame_options: "DENY".to_string(),
+            content_security_policy: "default-src 'self'".to_string(),
+            referrer_policy: "strict-origin-when-cross-origin".to_string(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+/// 路径前缀的响应头覆盖，例如接口文档页面需要放宽 CSP
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecurityHeadersOverride {
+    pub path: String,
+    pub headers: BTreeMap<String, String>,
+}
+
+type HeaderList = Vec<(HeaderName, Option<HeaderValue>)>;
+
+/// 安全响应头中间件状态
+#[derive(Clone)]
+pub struct SecurityHeaders {
+    defaults: Arc<HeaderList>,
+    overrides: Arc<Vec<(String, HeaderList)>>,
+}
+
+impl SecurityHeaders {
+    pub fn new(config: &SecurityHeadersConfig) -> Result<Self> {
+        let defaults = [
+            (STRICT_TRANSPORT_SECURITY, &config.hsts),
+            (X_CONTENT_TYPE_OPTIONS, &config.content_type_options),
+            (X_FRAME_OPTIONS, &config.frame_options),
+            (CONTENT_SECURITY_POLICY, &config.content_security_policy),
+            (REFERRER_POLICY, &config.referrer_policy),
+        ]
+        .into_iter()
+        .map(|(name, value)| Ok((name, parse_value(value)?)))
+        .collect::<Result<_>>()?;
+
+        let mut overrides = config
+            .overrides
+            .iter()
+            .map(|item| {
+                let headers = item
+                    .headers
+                    .iter()
+                    .map(|(name, value)| {
+                        let name = HeaderName::from_str(name)
+                            .with_context(|| format!("invalid security header name '{}'", name))?;
+                        Ok((name, parse_value(value)?))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((item.path.clone(), headers))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        overrides.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+
+        Ok(Self { defaults: Arc::new(defaults), overrides: Arc::new(overrides) })
+    }
+}
+
+fn parse_value(value: &str) -> Result<Option<HeaderValue>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let value = HeaderValue::from_str(value).with_context(|| format!("invalid security header value '{}'", value))?;
+    Ok(Some(value))
+}
+
+/// 安全响应头中间件，配合 `axum::middleware::from_fn_with_state` 使用
+pub async fn security_headers(State(state): State<SecurityHeaders>, req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    let mut resp = next.run(req).await;
+
+    let mut headers: Vec<&(HeaderName, Option<HeaderValue>)> = state.defaults.iter().collect();
+    if let Some((_, overrides)) = state.overrides.iter().find(|(prefix, _)| path_has_prefix(&path, prefix)) {
+        headers.retain(|(name, _)| !overrides.iter().any(|(item, _)| item == name));
+        headers.extend(overrides.iter());
+    }
+
+    for (name, value) in headers {
+        if let Some(value) = value {
+            resp.headers_mut().entry(name).or_insert(value.clone());
+        }
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_security_headers() {
+        let config = SecurityHeadersConfig {
+            overrides: vec![SecurityHeadersOverride {
+                path: "/docs".to_string(),
+                headers: BTreeMap::from([
+                    ("content-security-policy".to_string(), "default-src 'self' 'unsafe-inline'".to_string()),
+                    ("x-frame-options".to_string(), "".to_string()),
+                ]),
+            }],
+            ..Default::default()
+        };
+        let state = SecurityHeaders::new(&config).unwrap();
+        let router = Router::new()
+            .route("/api", get(|| async { "OK" }))
+            .route("/docs/ui", get(|| async { "OK" }))
+            .route("/docsx", get(|| async { "OK" }))
+            .route("/frame", get(|| async { ([(X_FRAME_OPTIONS, "SAMEORIGIN")], "OK") }))
+            .layer(axum::middleware::from_fn_with_state(state, security_headers));
+
+        let send = |uri: &str| router.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+
+        let resp = send("/api").await.unwrap();
+        assert_eq!(resp.headers()[X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(resp.headers()[X_FRAME_OPTIONS], "DENY");
+        assert_eq!(resp.headers()[CONTENT_SECURITY_POLICY], "default-src 'self'");
+
+        let resp = send("/docs/ui").await.unwrap();
+        assert_eq!(resp.headers()[CONTENT_SECURITY_POLICY], "default-src 'self' 'unsafe-inline'");
+        assert!(resp.headers().get(X_FRAME_OPTIONS).is_none());
+        assert_eq!(resp.headers()[REFERRER_POLICY], "strict-origin-when-cross-origin");
+
+        let resp = send("/docsx").await.unwrap();
+        assert_eq!(resp.headers()[CONTENT_SECURITY_POLICY], "default-src 'self'");
+
+        let resp = send("/frame").await.unwrap();
+        assert_eq!(resp.headers()[X_FRAME_OPTIONS], "SAMEORIGIN");
+    }
+}
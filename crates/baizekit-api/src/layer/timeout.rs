@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::response::Reply;
+
+/// 请求超时中间件，配合 `axum::middleware::from_fn_with_state` 使用
+///
+/// 超时返回 `Reply` 格式的 504；仅限制返回响应头前的处理时间，不限制流式响应体的传输。
+pub async fn request_timeout(State(timeout): State<Duration>, req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+
+    match tokio::time::timeout(timeout, next.run(req)).await {
+        Ok(resp) => resp,
+        Err(_) => {
+            tracing::warn!(%method, path, "request timed out after {:?}", timeout);
+            let status = StatusCode::GATEWAY_TIMEOUT;
+            let reply = Reply::<()> { code: status.as_u16() as i32, message: "GatewayTimeout".to_string(), data: None };
+            (status, reply).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let router = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    "OK"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(Duration::from_millis(10), request_timeout));
+
+        let resp = router
+            .oneshot(Request::get("/slow").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let reply: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["code"], 504);
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::extract::MatchedPath;
+use axum::http::{Request, Response};
+use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
+use tower_http::request_id::RequestId;
+use tower_http::trace::{DefaultOnResponse, OnResponse, TraceLayer};
+use tracing::field::Empty;
+use tracing::{Level, Span, info};
+
+/// [`trace_layer`] 返回的 layer 类型
+pub type HttpTraceLayer = TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    fn(&Request<Body>) -> Span,
+    fn(&Request<Body>, &Span),
+    TraceOnResponse,
+>;
+
+/// 请求追踪 layer，span 属性遵循 OpenTelemetry HTTP 语义约定
+///
+/// 启用 `otel` feature 时会从 `traceparent`/`tracestate` 请求头中恢复上游链路上下文。
+pub fn trace_layer(level: Level) -> HttpTraceLayer {
+    TraceLayer::new_for_http()
+        .make_span_with(make_span as fn(&Request<Body>) -> Span)
+        .on_request(on_request as fn(&Request<Body>, &Span))
+        .on_response(TraceOnResponse(DefaultOnResponse::new().level(level)))
+}
+
+/// 记录响应状态码到 span 后再输出响应日志
+#[derive(Clone)]
+pub struct TraceOnResponse(DefaultOnResponse);
+
+impl<B> OnResponse<B> for TraceOnResponse {
+    fn on_response(self, response: &Response<B>, latency: Duration, span: &Span) {
+        let status = response.status();
+        span.record("http.response.status_code", i64::from(status.as_u16()));
+        if status.is_server_error() {
+            span.record("otel.status_code", "ERROR");
+        }
+        self.0.on_response(response, latency, span);
+    }
+}
+
+fn make_span(request: &Request<Body>) -> Span {
+    let x_request_id = request.extensions().get::<RequestId>();
+    // 使用路由模板而非原始路径，避免 span 名称基数过高
+    let route = request.extensions().get::<MatchedPath>().map(|path| path.as_str());
+    let method = request.method().as_str();
+    let name = match route {
+        Some(route) => format!("{} {}", method, route),
+        None => method.to_string(),
+    };
+
+    let span = tracing::info_span!(
+        "request",
+        otel.name = name,
+        otel.kind = "server",
+        otel.status_code = Empty,
+        ?x_request_id,
+        http.request.method = method,
+        http.route = route,
+        http.response.status_code = Empty,
+        url.path = request.uri().path(),
+        url.query = request.uri().query(),
+        network.protocol.version = ?request.version(),
+        user_agent.original = request.headers().get("user-agent").and_then(|v| v.to_str().ok()),
+    );
+
+    #[cfg(feature = "otel")]
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&opentelemetry_http::HeaderExtractor(request.headers()))
+        });
+        span.set_parent(parent);
+    }
+
+    span
+}
+
+fn on_request(request: &Request<Body>, _span: &Span) {
+    info!("request: {} {}", request.method(), request.uri().path());
+}
+
+/// 将当前 span 的链路上下文写入请求头，用于调用下游服务时传播 `traceparent`/`tracestate`
+#[cfg(feature = "otel")]
+pub fn inject_trace_context(headers: &mut axum::http::HeaderMap) {
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut opentelemetry_http::HeaderInjector(headers))
+    });
+}
+
+/// 返回仅包含当前链路上下文的请求头，便于传给 HTTP 客户端
+#[cfg(feature = "otel")]
+pub fn trace_context_headers() -> axum::http::HeaderMap {
+    let mut headers = axum::http::HeaderMap::new();
+    inject_trace_context(&mut headers);
+    headers
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+    use opentelemetry::Value;
+    use opentelemetry::trace::{SpanId, TraceId, TracerProvider};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_trace_context_propagation() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new()
+            .route(
+                "/users/{id}",
+                get(|| async {
+                    let headers = trace_context_headers();
+                    headers["traceparent"].to_str().unwrap().to_string()
+                }),
+            )
+            .layer(trace_layer(Level::INFO));
+
+        let req = Request::get("/users/42")
+            .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .header("tracestate", "vendor=value")
+            .body(Body::empty())
+            .unwrap();
+        let resp = router.oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let outbound = String::from_utf8(body.to_vec()).unwrap();
+        assert!(outbound.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"), "{}", outbound);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans.iter().find(|span| span.name == "GET /users/{id}").unwrap();
+        assert_eq!(span.span_context.trace_id(), TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap());
+        assert_eq!(span.parent_span_id, SpanId::from_hex("00f067aa0ba902b7").unwrap());
+        assert_eq!(span.span_context.trace_state().get("vendor"), Some("value"));
+
+        let attr = |key: &str| {
+            span.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(attr("http.route"), Some(Value::from("/users/{id}")));
+        assert_eq!(attr("http.response.status_code"), Some(Value::I64(200)));
+    }
+}
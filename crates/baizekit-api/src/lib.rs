@@ -1,12 +1,25 @@
 #[cfg(feature = "http-build")]
 pub mod build;
-
+#[cfg(feature = "client")]
+pub mod client;
 pub mod component;
 pub mod extract;
+pub mod layer;
 pub mod response;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "upload")]
+pub mod upload;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 pub mod prelude {
     pub use crate::component::axum::*;
     pub use crate::extract::*;
+    pub use crate::layer::*;
     pub use crate::response::*;
+    #[cfg(feature = "upload")]
+    pub use crate::upload::*;
+    #[cfg(feature = "ws")]
+    pub use crate::ws::*;
 }
@@ -1,7 +1,6 @@
 use std::error::Error;
 
 use axum::response::{IntoResponse, Response};
-use axum::Json;
 use derive_more::From;
 
 use crate::response::Reply;
@@ -39,6 +38,6 @@ where
     fn into_response(self) -> Response {
         let reply = Reply::<()>::from(self);
         tracing::error!("ErrorResponse: {:?}", reply);
-        Json(reply).into_response()
+        reply.into_response()
     }
 }
@@ -1,10 +1,12 @@
 mod err;
 mod msg;
 mod ok;
+mod stream;
 
 pub use err::*;
 pub use msg::*;
 pub use ok::*;
+pub use stream::*;
 
 pub type Result<T, E> = std::result::Result<T, ApiError<E>>;
 pub type ApiResult<T, E> = std::result::Result<ApiOK<T>, ApiError<E>>;
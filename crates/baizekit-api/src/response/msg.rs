@@ -1,6 +1,14 @@
 use std::fmt::{Debug, Formatter};
 
-#[derive(Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::extract::{PageQuery, encode_cursor};
+use crate::layer::render_requested;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct Reply<T = ()>
 where
     T: serde::Serialize,
@@ -23,6 +31,43 @@ where
     }
 }
 
+/// 序列化后的 [`Reply`]，存放在响应扩展中，供 [`crate::layer::reply_negotiation`] 重新编码
+///
+/// 仅在需要重新编码时(非 JSON 格式、[`crate::layer::raw_reply`] 或附加字段)生成，默认直接序列化 `T`，保持字段顺序。
+#[derive(Debug, Clone)]
+pub struct RenderedReply(pub Reply<serde_json::Value>);
+
+/// 标记响应由 [`Reply`] 生成，用于设置 `Vary: Accept`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReplyMarker;
+
+impl<T> IntoResponse for Reply<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response {
+        if !render_requested() {
+            let mut resp = Json(&self).into_response();
+            resp.extensions_mut().insert(ReplyMarker);
+            return resp;
+        }
+
+        let data = match self.data.map(serde_json::to_value).transpose() {
+            Ok(data) => data,
+            Err(err) => {
+                let reply = Reply::<()> { code: 500, message: format!("InternalServerError: {}", err), data: None };
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(reply)).into_response();
+            }
+        };
+
+        let reply = Reply { code: self.code, message: self.message, data };
+        let mut resp = Json(&reply).into_response();
+        resp.extensions_mut().insert(RenderedReply(reply));
+        resp.extensions_mut().insert(ReplyMarker);
+        resp
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
 pub struct Page<T> {
     /// 总记录数
@@ -45,3 +90,55 @@ impl<T> Debug for Page<T> {
             .finish()
     }
 }
+
+impl<T> Page<T> {
+    /// 由 `SearchTrait::search` 返回的 `(数据, 总数, 是否有更多)` 构建分页结果
+    pub fn from_search(query: &PageQuery, (data, total, _): (Vec<T>, u64, bool)) -> Self {
+        Self { total, current: query.page, size: query.size, data }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page { total: self.total, current: self.current, size: self.size, data: self.data.into_iter().map(f).collect() }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct CursorPage<T> {
+    /// 是否还有下一页
+    pub has_more: bool,
+    /// 下一页游标令牌，没有更多数据时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// 当前页数据
+    pub data: Vec<T>,
+}
+
+impl<T> CursorPage<T> {
+    /// 由 `SearchTrait::search` 返回的结果构建游标分页，`cursor_of` 从本页最后一条数据生成下一页游标
+    pub fn from_search<C, F>((data, _, has_more): (Vec<T>, u64, bool), cursor_of: F) -> Self
+    where
+        C: Serialize,
+        F: FnOnce(&T) -> C,
+    {
+        let next_cursor = if has_more { data.last().map(|last| encode_cursor(&cursor_of(last))) } else { None };
+        Self { has_more, next_cursor, data }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> CursorPage<U> {
+        CursorPage {
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+            data: self.data.into_iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Debug for CursorPage<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorPage")
+            .field("has_more", &self.has_more)
+            .field("next_cursor", &self.next_cursor)
+            .field("data", &self.data.len())
+            .finish()
+    }
+}
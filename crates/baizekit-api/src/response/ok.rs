@@ -1,5 +1,4 @@
 use axum::response::{IntoResponse, Response};
-use axum::Json;
 use serde::Serialize;
 
 use crate::response::Reply;
@@ -17,8 +16,7 @@ where
     T: Serialize,
 {
     fn into_response(self) -> Response {
-        let resp = Reply::ok(self.0);
-        Json(resp).into_response()
+        Reply::ok(self.0).into_response()
     }
 }
 
@@ -0,0 +1,240 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::time::Duration;
+
+use axum::body::{Body, Bytes};
+use axum::http::HeaderValue;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use crate::response::{ApiError, ErrorCode, Reply};
+
+/// 默认保活间隔
+const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// NDJSON 响应的 Content-Type
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// 将 `Stream<Item = Result<T, E>>` 以 Server-Sent Events 输出，例如 `SearchStreamTrait::stream` 的结果
+///
+/// 每一项输出为一个事件，数据为 `T` 的 JSON；遇到错误时输出 `event: error`，数据为 [`Reply`]，随后结束。
+/// 流按客户端读取速度拉取，不会在内存中缓冲全部数据。
+pub struct SseStream<S> {
+    stream: S,
+    event: Option<String>,
+    keep_alive: Option<Duration>,
+}
+
+impl<S> SseStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self { stream, event: None, keep_alive: Some(DEFAULT_KEEP_ALIVE) }
+    }
+
+    /// 数据事件的名称，未设置时客户端按 `message` 事件处理
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// 保活间隔，空闲时发送注释帧，None 时不发送
+    pub fn with_keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+}
+
+impl<S, T, E> IntoResponse for SseStream<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize,
+    E: ErrorCode + Error,
+{
+    fn into_response(self) -> Response {
+        let name = self.event;
+        let events = frames(self.stream).map(move |frame| {
+            let event = match frame {
+                Frame::Data(json) => match &name {
+                    Some(name) => Event::default().event(name).data(json),
+                    None => Event::default().data(json),
+                },
+                Frame::Error(json) => Event::default().event("error").data(json),
+            };
+            Ok::<_, Infallible>(event)
+        });
+
+        let sse = Sse::new(events);
+        let mut resp = match self.keep_alive {
+            Some(interval) => sse.keep_alive(KeepAlive::new().interval(interval)).into_response(),
+            None => sse.into_response(),
+        };
+        disable_proxy_buffering(&mut resp);
+        resp
+    }
+}
+
+/// 将 `Stream<Item = Result<T, E>>` 以换行分隔的 JSON(NDJSON)输出，适合大批量导出
+///
+/// 每一项输出为一行 `T` 的 JSON；遇到错误时输出一行 [`Reply`]，随后结束。空闲时输出空行保活。
+pub struct NdJsonStream<S> {
+    stream: S,
+    keep_alive: Option<Duration>,
+}
+
+impl<S> NdJsonStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self { stream, keep_alive: Some(DEFAULT_KEEP_ALIVE) }
+    }
+
+    /// 保活间隔，空闲时输出空行，None 时不输出
+    pub fn with_keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+}
+
+impl<S, T, E> IntoResponse for NdJsonStream<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize,
+    E: ErrorCode + Error,
+{
+    fn into_response(self) -> Response {
+        let lines = frames(self.stream).map(|frame| {
+            let (Frame::Data(mut json) | Frame::Error(mut json)) = frame;
+            json.push('\n');
+            Bytes::from(json)
+        });
+
+        let body = match self.keep_alive {
+            Some(interval) => Body::from_stream(keep_alive(lines, interval).map(Ok::<_, Infallible>)),
+            None => Body::from_stream(lines.map(Ok::<_, Infallible>)),
+        };
+
+        let mut resp = ([(CONTENT_TYPE, NDJSON_CONTENT_TYPE)], body).into_response();
+        disable_proxy_buffering(&mut resp);
+        resp
+    }
+}
+
+enum Frame {
+    Data(String),
+    Error(String),
+}
+
+/// 将每一项序列化为 JSON，遇到第一个错误时输出错误帧并结束
+fn frames<S, T, E>(stream: S) -> impl Stream<Item = Frame> + Send + 'static
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize,
+    E: ErrorCode + Error,
+{
+    futures_util::stream::unfold((Box::pin(stream), false), |(mut stream, done)| async move {
+        if done {
+            return None;
+        }
+
+        let reply = match stream.next().await? {
+            Ok(data) => match serde_json::to_string(&data) {
+                Ok(json) => return Some((Frame::Data(json), (stream, false))),
+                Err(err) => Reply { code: 500, message: format!("InternalServerError: {}", err), data: None },
+            },
+            Err(err) => Reply::from(ApiError(err)),
+        };
+
+        tracing::error!("StreamErrorResponse: {:?}", reply);
+        let json = serde_json::to_string(&reply).unwrap_or_default();
+        Some((Frame::Error(json), (stream, true)))
+    })
+}
+
+/// 在 `interval` 内没有数据时输出空行
+fn keep_alive<S>(stream: S, interval: Duration) -> impl Stream<Item = Bytes> + Send + 'static
+where
+    S: Stream<Item = Bytes> + Send + 'static,
+{
+    futures_util::stream::unfold(Box::pin(stream), move |mut stream| async move {
+        match tokio::time::timeout(interval, stream.next()).await {
+            Ok(Some(bytes)) => Some((bytes, stream)),
+            Ok(None) => None,
+            Err(_) => Some((Bytes::from_static(b"\n"), stream)),
+        }
+    })
+}
+
+/// 关闭 Nginx 等反向代理的响应缓冲，保证数据及时送达
+fn disable_proxy_buffering(resp: &mut Response) {
+    resp.headers_mut().insert("x-accel-buffering", HeaderValue::from_static("no"));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ExportError;
+
+    impl Display for ExportError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "export interrupted")
+        }
+    }
+
+    impl Error for ExportError {}
+
+    impl ErrorCode for ExportError {
+        fn code(&self) -> i32 {
+            10001
+        }
+    }
+
+    fn items() -> impl Stream<Item = Result<u32, ExportError>> + Send + 'static {
+        futures_util::stream::iter([Ok(1), Ok(2), Err(ExportError), Ok(3)])
+    }
+
+    async fn body_text(resp: Response) -> String {
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream() {
+        let resp = SseStream::new(items()).with_event("row").into_response();
+        assert_eq!(resp.headers()[CONTENT_TYPE], "text/event-stream");
+
+        let body = body_text(resp).await;
+        assert_eq!(
+            body,
+            "event: row\ndata: 1\n\nevent: row\ndata: 2\n\n\
+             event: error\ndata: {\"code\":10001,\"message\":\"export interrupted\"}\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_stream() {
+        let resp = NdJsonStream::new(items()).into_response();
+        assert_eq!(resp.headers()[CONTENT_TYPE], NDJSON_CONTENT_TYPE);
+
+        let body = body_text(resp).await;
+        assert_eq!(body, "1\n2\n{\"code\":10001,\"message\":\"export interrupted\"}\n");
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_keep_alive() {
+        let slow = futures_util::stream::once(async {
+            tokio::time::sleep(Duration::from_millis(80)).await;
+            Ok::<_, ExportError>(1)
+        });
+        let resp = NdJsonStream::new(slow)
+            .with_keep_alive(Some(Duration::from_millis(20)))
+            .into_response();
+
+        let body = body_text(resp).await;
+        assert!(body.starts_with('\n'));
+        assert!(body.ends_with("\n1\n"));
+    }
+}
@@ -0,0 +1,362 @@
+//! HTTP 集成测试工具
+//!
+//! 由 [`AxumComponentBuilder`] 构建路由但不绑定端口，通过 `oneshot` 在进程内发送请求：
+//!
+//! ```ignore
+//! let server = TestServer::new(builder, AxumComponentConfig::default())?;
+//! server.get("/api/users/1").principal(&principal).send().await
+//!     .assert_status(StatusCode::OK)
+//!     .reply::<User>()
+//!     .assert_code(0)
+//!     .assert_data(&expected);
+//! ```
+use std::fmt::Debug;
+
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode};
+use baizekit_app::anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use tower::ServiceExt;
+
+use crate::component::axum::{AxumComponentBuilder, AxumComponentConfig, DEFAULT_LISTENER};
+use crate::extract::{AdminPrincipal, EndUserPrincipal, Principal, PrincipalSigner};
+use crate::response::Reply;
+
+/// 进程内的测试服务，包含完整的中间件栈
+#[derive(Clone)]
+pub struct TestServer {
+    router: Router,
+    signer: Option<PrincipalSigner>,
+}
+
+impl TestServer {
+    /// 构建默认监听器的路由
+    pub fn new(builder: AxumComponentBuilder, conf: AxumComponentConfig) -> Result<Self> {
+        Self::with_listener(builder, conf, DEFAULT_LISTENER)
+    }
+
+    /// 构建指定监听器的路由
+    pub fn with_listener(builder: AxumComponentBuilder, conf: AxumComponentConfig, listener: &str) -> Result<Self> {
+        let routes = std::sync::Arc::new(builder.route_registry(&conf)?);
+        let router = builder.build_router(listener, &conf, &routes)?;
+        let signer = PrincipalSigner::new(&conf.principal_signing);
+        Ok(Self { router, signer })
+    }
+
+    /// 直接使用已构建的路由
+    pub fn from_router(router: Router) -> Self {
+        Self { router, signer: None }
+    }
+
+    /// 注入身份时使用的签名，默认读取自配置的 `principal_signing`
+    pub fn with_signer(mut self, signer: PrincipalSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    pub fn request(&self, method: Method, path: impl Into<String>) -> TestRequest {
+        TestRequest {
+            router: self.router.clone(),
+            signer: self.signer.clone(),
+            method,
+            path: path.into(),
+            headers: HeaderMap::new(),
+            body: Body::empty(),
+        }
+    }
+
+    pub fn get(&self, path: impl Into<String>) -> TestRequest {
+        self.request(Method::GET, path)
+    }
+
+    pub fn post(&self, path: impl Into<String>) -> TestRequest {
+        self.request(Method::POST, path)
+    }
+
+    pub fn put(&self, path: impl Into<String>) -> TestRequest {
+        self.request(Method::PUT, path)
+    }
+
+    pub fn patch(&self, path: impl Into<String>) -> TestRequest {
+        self.request(Method::PATCH, path)
+    }
+
+    pub fn delete(&self, path: impl Into<String>) -> TestRequest {
+        self.request(Method::DELETE, path)
+    }
+}
+
+/// 待发送的测试请求，构建失败时直接 panic
+pub struct TestRequest {
+    router: Router,
+    signer: Option<PrincipalSigner>,
+    method: Method,
+    path: String,
+    headers: HeaderMap,
+    body: Body,
+}
+
+impl TestRequest {
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = HeaderName::try_from(name.as_ref()).expect("invalid header name");
+        let value = HeaderValue::try_from(value.as_ref()).expect("invalid header value");
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// 设置 JSON 请求体
+    pub fn json<T: Serialize>(mut self, body: &T) -> Self {
+        self.body = Body::from(serde_json::to_vec(body).expect("failed to serialize request body"));
+        self.header(CONTENT_TYPE, "application/json")
+    }
+
+    pub fn body(mut self, body: impl Into<Body>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// 注入网关透传的终端用户身份，配置了签名时同时附带签名
+    pub fn principal(self, principal: &EndUserPrincipal) -> Self {
+        self.with_principal(principal)
+    }
+
+    /// 注入网关透传的管理员身份，配置了签名时同时附带签名
+    pub fn admin_principal(self, principal: &AdminPrincipal) -> Self {
+        self.with_principal(principal)
+    }
+
+    fn with_principal<P: Principal>(mut self, principal: &P) -> Self {
+        match &self.signer {
+            Some(signer) => self.headers.extend(signer.headers(principal)),
+            None => {
+                let value = serde_json::to_string(principal).expect("failed to serialize principal");
+                return self.header(P::HEADER, value);
+            }
+        }
+        self
+    }
+
+    pub fn bearer(self, token: impl AsRef<str>) -> Self {
+        self.header(AUTHORIZATION, format!("Bearer {}", token.as_ref()))
+    }
+
+    /// 使用 HS256 签发 JWT 并作为 Bearer Token 发送
+    pub fn jwt<C: Serialize>(self, claims: &C, secret: &[u8]) -> Self {
+        let token = sign_jwt(claims, secret);
+        self.bearer(token)
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let mut req = Request::builder()
+            .method(self.method)
+            .uri(self.path)
+            .body(self.body)
+            .expect("invalid request");
+        req.headers_mut().extend(self.headers);
+
+        let resp = self.router.oneshot(req).await.expect("router is infallible");
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("failed to read response body");
+        TestResponse { status, headers, body }
+    }
+}
+
+/// 使用 HS256 签发 JWT
+pub fn sign_jwt<C: Serialize>(claims: &C, secret: &[u8]) -> String {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).expect("failed to serialize claims"));
+    let message = format!("{}.{}", header, payload);
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(message.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+    format!("{}.{}", message, signature)
+}
+
+/// 已读取完整响应体的测试响应
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn header(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.headers.get(name.as_ref()).and_then(|v| v.to_str().ok())
+    }
+
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|err| panic!("failed to decode response body: {}\nbody: {}", err, self.text()))
+    }
+
+    /// 解码为 `Reply<T>`
+    pub fn reply<T: Serialize + DeserializeOwned>(&self) -> TestReply<T> {
+        TestReply(self.json())
+    }
+
+    #[track_caller]
+    pub fn assert_status(self, status: StatusCode) -> Self {
+        assert_eq!(self.status, status, "unexpected status, body: {}", self.text());
+        self
+    }
+
+    #[track_caller]
+    pub fn assert_header(self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        assert_eq!(self.header(name.as_ref()), Some(value.as_ref()), "unexpected header '{}'", name.as_ref());
+        self
+    }
+}
+
+/// 解码后的 `Reply`，提供针对 `code`、`message` 和 `data` 的断言
+#[derive(Debug, Clone)]
+pub struct TestReply<T: Serialize>(pub Reply<T>);
+
+impl<T: Serialize> TestReply<T> {
+    pub fn code(&self) -> i32 {
+        self.0.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    pub fn data(&self) -> Option<&T> {
+        self.0.data.as_ref()
+    }
+
+    pub fn into_data(self) -> Option<T> {
+        self.0.data
+    }
+
+    #[track_caller]
+    pub fn assert_ok(self) -> Self {
+        self.assert_code(0)
+    }
+
+    #[track_caller]
+    pub fn assert_code(self, code: i32) -> Self {
+        assert_eq!(self.0.code, code, "unexpected reply code, message: {}", self.0.message);
+        self
+    }
+
+    #[track_caller]
+    pub fn assert_message(self, message: impl AsRef<str>) -> Self {
+        assert_eq!(self.0.message, message.as_ref(), "unexpected reply message");
+        self
+    }
+}
+
+impl<T: Serialize + PartialEq + Debug> TestReply<T> {
+    #[track_caller]
+    pub fn assert_data(self, data: &T) -> Self {
+        assert_eq!(self.0.data.as_ref(), Some(data), "unexpected reply data");
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Json;
+    use axum::routing::{get, post};
+    use utoipa::openapi::{Info, OpenApi, Paths};
+
+    use super::*;
+    use crate::component::axum::AxumServiceInfo;
+    use crate::response::ApiOK;
+
+    fn server() -> TestServer {
+        let router = Router::new()
+            .route("/me", get(|principal: EndUserPrincipal| async move { ApiOK::with_data(principal.account) }))
+            .route("/echo", post(|Json(body): Json<serde_json::Value>| async move { ApiOK::with_data(body) }))
+            .route(
+                "/token",
+                get(|headers: HeaderMap| async move { headers[AUTHORIZATION].to_str().unwrap().to_string() }),
+            );
+        let service =
+            AxumServiceInfo::new("/api", router, OpenApi::new(Info::new("", ""), Paths::new())).with_routes([
+                (Method::GET, "/me"),
+                (Method::POST, "/echo"),
+                (Method::GET, "/token"),
+            ]);
+        let builder = AxumComponentBuilder::new().add_service(service);
+        TestServer::new(builder, AxumComponentConfig::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_server() {
+        let server = server();
+
+        server.get("/health").send().await.assert_status(StatusCode::OK);
+        server.get("/api/me").send().await.assert_status(StatusCode::UNAUTHORIZED);
+
+        let principal = EndUserPrincipal { id: 1, account: "alice".to_string(), tenant_id: "t1".to_string() };
+        server
+            .get("/api/me")
+            .principal(&principal)
+            .send()
+            .await
+            .assert_status(StatusCode::OK)
+            .reply::<String>()
+            .assert_ok()
+            .assert_message("OK")
+            .assert_data(&"alice".to_string());
+
+        let body = serde_json::json!({ "name": "bob" });
+        let reply = server.post("/api/echo").json(&body).send().await.reply::<serde_json::Value>();
+        assert_eq!(reply.into_data(), Some(body));
+    }
+
+    #[tokio::test]
+    async fn test_jwt() {
+        // 字段顺序与 jwt.io 的示例一致，结果应与示例令牌完全相同
+        #[derive(Serialize)]
+        struct Claims {
+            sub: &'static str,
+            name: &'static str,
+            iat: i64,
+        }
+        let claims = Claims { sub: "1234567890", name: "John Doe", iat: 1516239022 };
+        let token = sign_jwt(&claims, b"your-256-bit-secret");
+        assert_eq!(
+            token,
+            "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+             eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+             SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c"
+        );
+
+        let resp = server().get("/api/token").jwt(&claims, b"secret").send().await;
+        let token = resp.text().strip_prefix("Bearer ").unwrap().to_string();
+        let (message, signature) = token.rsplit_once('.').unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(message.as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(signature).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::FromRequestParts;
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes, WebSocket, WebSocketUpgrade};
+use axum::http::StatusCode;
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use baizekit_app::anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::extract::{AdminPrincipal, EndUserPrincipal};
+use crate::response::Reply;
+
+/// 服务关闭时的关闭码(Going Away)
+const CLOSE_GOING_AWAY: u16 = 1001;
+/// 处理函数来不及消费客户端消息时的关闭码(Try Again Later)
+const CLOSE_TRY_AGAIN_LATER: u16 = 1013;
+
+/// WebSocket 配置，读取自 `axum.{label}.ws`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WsConfig {
+    /// 服务端发送 Ping 的间隔(秒)
+    pub heartbeat_interval_seconds: u64,
+    /// 超过该时间(秒)未收到客户端任何帧时关闭连接
+    pub idle_timeout_seconds: u64,
+    /// 单条消息大小上限(字节)
+    pub max_message_size: usize,
+    /// 每个连接的收发队列长度，发送队列满时丢弃广播消息，接收队列满时以 1013 关闭连接
+    pub send_buffer: usize,
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self { heartbeat_interval_seconds: 30, idle_timeout_seconds: 90, max_message_size: 64 << 10, send_buffer: 64 }
+    }
+}
+
+/// 可用于 WebSocket 连接的认证主体，提供广播使用的用户和租户标识
+pub trait WsPrincipal: Send + Sync + 'static {
+    fn user_id(&self) -> String;
+
+    fn tenant_id(&self) -> String;
+}
+
+impl WsPrincipal for AdminPrincipal {
+    fn user_id(&self) -> String {
+        self.admin_id.to_string()
+    }
+
+    fn tenant_id(&self) -> String {
+        self.tenant_id.clone()
+    }
+}
+
+impl WsPrincipal for EndUserPrincipal {
+    fn user_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn tenant_id(&self) -> String {
+        self.tenant_id.clone()
+    }
+}
+
+struct WsPeer {
+    user_id: String,
+    tenant_id: String,
+    sender: mpsc::Sender<Message>,
+}
+
+struct WsHubInner {
+    config: RwLock<WsConfig>,
+    peers: RwLock<HashMap<u64, WsPeer>>,
+    next_id: AtomicU64,
+    shutdown: CancellationToken,
+}
+
+/// WebSocket 连接注册表，支持按用户、租户广播
+///
+/// 通过 [`crate::component::axum::AxumComponentBuilder::with_ws_hub`] 注册后，
+/// 配置读取自 `axum.{label}.ws`，组件关闭时会关闭所有连接。
+#[derive(Clone)]
+pub struct WsHub {
+    inner: Arc<WsHubInner>,
+}
+
+impl Default for WsHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WsHub {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(WsHubInner {
+                config: RwLock::new(WsConfig::default()),
+                peers: RwLock::new(HashMap::new()),
+                next_id: AtomicU64::new(1),
+                shutdown: CancellationToken::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn configure(&self, config: WsConfig) {
+        *self.inner.config.write().unwrap() = config;
+    }
+
+    fn config(&self) -> WsConfig {
+        self.inner.config.read().unwrap().clone()
+    }
+
+    /// 当前连接数
+    pub fn len(&self) -> usize {
+        self.inner.peers.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 发送给指定用户的所有连接，返回送达的连接数
+    pub fn send_to_user<T: Serialize>(&self, user_id: &str, msg: &T) -> Result<usize> {
+        self.send_where(msg, |peer| peer.user_id == user_id)
+    }
+
+    /// 发送给指定租户的所有连接，返回送达的连接数
+    pub fn send_to_tenant<T: Serialize>(&self, tenant_id: &str, msg: &T) -> Result<usize> {
+        self.send_where(msg, |peer| peer.tenant_id == tenant_id)
+    }
+
+    /// 发送给所有连接，返回送达的连接数
+    pub fn broadcast<T: Serialize>(&self, msg: &T) -> Result<usize> {
+        self.send_where(msg, |_| true)
+    }
+
+    fn send_where<T: Serialize>(&self, msg: &T, filter: impl Fn(&WsPeer) -> bool) -> Result<usize> {
+        let text = Utf8Bytes::from(serde_json::to_string(msg)?);
+        let peers = self.inner.peers.read().unwrap();
+
+        let mut delivered = 0;
+        for (id, peer) in peers.iter().filter(|(_, peer)| filter(peer)) {
+            match peer.sender.try_send(Message::Text(text.clone())) {
+                Ok(()) => delivered += 1,
+                Err(err) => warn!("websocket {} drop message: {}", id, err),
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// 关闭所有连接，之后建立的连接也会被立即关闭
+    pub fn close_all(&self) {
+        self.inner.shutdown.cancel();
+    }
+
+    fn register(&self, user_id: String, tenant_id: String, sender: mpsc::Sender<Message>) -> u64 {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let peer = WsPeer { user_id, tenant_id, sender };
+        self.inner.peers.write().unwrap().insert(id, peer);
+        id
+    }
+
+    fn unregister(&self, id: u64) {
+        self.inner.peers.write().unwrap().remove(&id);
+    }
+}
+
+/// 已认证的 WebSocket 升级请求，认证失败时返回主体提取器的拒绝响应
+///
+/// ```ignore
+/// async fn notify(ws: WsRequest<EndUserPrincipal>) -> Response {
+///     ws.on_session(|mut session: WsSession<EndUserPrincipal, Command, Event>| async move {
+///         while let Some(cmd) = session.recv().await {
+///             session.send(&Event::from(cmd)).await;
+///         }
+///     })
+/// }
+/// ```
+pub struct WsRequest<P> {
+    upgrade: WebSocketUpgrade,
+    principal: P,
+    hub: WsHub,
+}
+
+impl<S, P> FromRequestParts<S> for WsRequest<P>
+where
+    S: Send + Sync,
+    P: WsPrincipal + FromRequestParts<S>,
+    P::Rejection: IntoResponse,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let principal = P::from_request_parts(parts, state).await.map_err(IntoResponse::into_response)?;
+        let Some(hub) = parts.extensions.get::<WsHub>().cloned() else {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "WsHub is not configured").into_response());
+        };
+        let upgrade = WebSocketUpgrade::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        Ok(Self { upgrade, principal, hub })
+    }
+}
+
+impl<P: WsPrincipal> WsRequest<P> {
+    pub fn principal(&self) -> &P {
+        &self.principal
+    }
+
+    /// 完成升级，并在独立任务中运行 `handler`；`handler` 返回后关闭连接
+    pub fn on_session<In, Out, F, Fut>(self, handler: F) -> Response
+    where
+        In: DeserializeOwned + Send + 'static,
+        Out: Serialize + Send + 'static,
+        F: FnOnce(WsSession<P, In, Out>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let Self { upgrade, principal, hub } = self;
+        let config = hub.config();
+
+        upgrade
+            .max_message_size(config.max_message_size)
+            .on_upgrade(move |socket| drive(socket, hub, config, principal, handler))
+    }
+}
+
+/// 单个 WebSocket 会话，收发均为 JSON
+pub struct WsSession<P, In, Out> {
+    id: u64,
+    principal: P,
+    incoming: mpsc::Receiver<In>,
+    outgoing: mpsc::Sender<Message>,
+    _out: PhantomData<fn(Out)>,
+}
+
+impl<P, In, Out> WsSession<P, In, Out>
+where
+    Out: Serialize,
+{
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn principal(&self) -> &P {
+        &self.principal
+    }
+
+    /// 接收下一条消息，连接关闭后返回 None
+    pub async fn recv(&mut self) -> Option<In> {
+        self.incoming.recv().await
+    }
+
+    /// 发送消息，连接已关闭时返回 false
+    pub async fn send(&self, msg: &Out) -> bool {
+        let text = match serde_json::to_string(msg) {
+            Ok(text) => text,
+            Err(err) => {
+                warn!("websocket {} encode message failed: {}", self.id, err);
+                return false;
+            }
+        };
+        self.outgoing.send(Message::Text(text.into())).await.is_ok()
+    }
+}
+
+/// 连接驱动：转发收发的消息、定时 Ping、检测空闲超时并在关闭时发送关闭帧
+async fn drive<P, In, Out, F, Fut>(mut socket: WebSocket, hub: WsHub, config: WsConfig, principal: P, handler: F)
+where
+    P: WsPrincipal,
+    In: DeserializeOwned + Send + 'static,
+    Out: Serialize + Send + 'static,
+    F: FnOnce(WsSession<P, In, Out>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (out_tx, mut out_rx) = mpsc::channel(config.send_buffer.max(1));
+    let (in_tx, in_rx) = mpsc::channel(config.send_buffer.max(1));
+    let id = hub.register(principal.user_id(), principal.tenant_id(), out_tx.clone());
+    let session = WsSession { id, principal, incoming: in_rx, outgoing: out_tx, _out: PhantomData };
+    let mut task = tokio::spawn(handler(session));
+
+    let idle_timeout = Duration::from_secs(config.idle_timeout_seconds);
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(config.heartbeat_interval_seconds.max(1)));
+    let mut last_seen = Instant::now();
+
+    let close = loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                let msg = match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => {
+                        debug!("websocket {} receive failed: {}", id, err);
+                        break None;
+                    }
+                    None => break None,
+                };
+                last_seen = Instant::now();
+
+                let decoded = match &msg {
+                    Message::Text(text) => serde_json::from_str::<In>(text),
+                    Message::Binary(bytes) => serde_json::from_slice::<In>(bytes),
+                    Message::Close(_) => break None,
+                    Message::Ping(_) | Message::Pong(_) => continue,
+                };
+                match decoded {
+                    // 不等待处理函数消费，避免与阻塞在发送上的处理函数互相等待
+                    Ok(msg) => match in_tx.try_send(msg) {
+                        Ok(()) => {}
+                        Err(mpsc::error::TrySendError::Full(_)) => {
+                            debug!("websocket {} incoming queue is full", id);
+                            break Some(close_frame(CLOSE_TRY_AGAIN_LATER, "too many pending messages"));
+                        }
+                        Err(mpsc::error::TrySendError::Closed(_)) => break Some(close_frame(1000, "")),
+                    },
+                    Err(err) => {
+                        let reply = Reply::<()> { code: 400, message: format!("invalid message: {}", err), data: None };
+                        let text = serde_json::to_string(&reply).unwrap_or_default();
+                        if socket.send(Message::Text(text.into())).await.is_err() {
+                            break None;
+                        }
+                    }
+                }
+            }
+            Some(msg) = out_rx.recv() => {
+                if socket.send(msg).await.is_err() {
+                    break None;
+                }
+            }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > idle_timeout {
+                    debug!("websocket {} idle timeout", id);
+                    break Some(close_frame(1000, "idle timeout"));
+                }
+                if socket.send(Message::Ping(Default::default())).await.is_err() {
+                    break None;
+                }
+            }
+            _ = &mut task => break Some(close_frame(1000, "")),
+            _ = hub.inner.shutdown.cancelled() => break Some(close_frame(CLOSE_GOING_AWAY, "server shutting down")),
+        }
+    };
+
+    hub.unregister(id);
+    drop(in_tx);
+    if let Some(frame) = close {
+        let _ = socket.send(Message::Close(Some(frame))).await;
+    }
+}
+
+fn close_frame(code: u16, reason: &'static str) -> CloseFrame {
+    CloseFrame { code, reason: Utf8Bytes::from_static(reason) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message as ClientMessage;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    use super::*;
+    use crate::extract::CUSTOM_PRINCIPAL_HEADER;
+
+    #[derive(Deserialize)]
+    struct Ping {
+        seq: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Pong {
+        seq: u32,
+    }
+
+    async fn echo(ws: WsRequest<EndUserPrincipal>) -> Response {
+        ws.on_session(|mut session: WsSession<EndUserPrincipal, Ping, Pong>| async move {
+            while let Some(ping) = session.recv().await {
+                session.send(&Pong { seq: ping.seq }).await;
+            }
+        })
+    }
+
+    async fn serve(hub: WsHub) -> SocketAddr {
+        let router = Router::new().route("/ws", get(echo)).layer(Extension(hub));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        addr
+    }
+
+    async fn connect(
+        addr: SocketAddr,
+        principal: Option<&str>,
+    ) -> std::result::Result<
+        tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+        tokio_tungstenite::tungstenite::Error,
+    > {
+        let mut req = format!("ws://{}/ws", addr).into_client_request().unwrap();
+        if let Some(principal) = principal {
+            req.headers_mut().insert(CUSTOM_PRINCIPAL_HEADER, principal.parse().unwrap());
+        }
+        tokio_tungstenite::connect_async(req).await.map(|(stream, _)| stream)
+    }
+
+    async fn next_text<S>(stream: &mut S) -> String
+    where
+        S: futures_util::Stream<Item = std::result::Result<ClientMessage, tokio_tungstenite::tungstenite::Error>>
+            + Unpin,
+    {
+        loop {
+            match stream.next().await.unwrap().unwrap() {
+                ClientMessage::Text(text) => return text.to_string(),
+                ClientMessage::Ping(_) | ClientMessage::Pong(_) => continue,
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ws_session_broadcast_and_shutdown() {
+        let hub = WsHub::new();
+        let addr = serve(hub.clone()).await;
+
+        assert!(connect(addr, None).await.is_err());
+
+        let principal = r#"{"id":7,"account":"alice","tenant_id":"t1"}"#;
+        let mut client = connect(addr, Some(principal)).await.unwrap();
+
+        client.send(ClientMessage::text(r#"{"seq":1}"#)).await.unwrap();
+        assert_eq!(next_text(&mut client).await, r#"{"seq":1}"#);
+
+        client.send(ClientMessage::text("not json")).await.unwrap();
+        assert!(next_text(&mut client).await.contains(r#""code":400"#));
+
+        assert_eq!(hub.send_to_user("7", &Pong { seq: 2 }).unwrap(), 1);
+        assert_eq!(next_text(&mut client).await, r#"{"seq":2}"#);
+        assert_eq!(hub.send_to_tenant("t2", &Pong { seq: 3 }).unwrap(), 0);
+
+        hub.close_all();
+        loop {
+            match client.next().await.unwrap().unwrap() {
+                ClientMessage::Close(Some(frame)) => break assert_eq!(u16::from(frame.code), CLOSE_GOING_AWAY),
+                ClientMessage::Ping(_) | ClientMessage::Pong(_) => continue,
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(hub.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_with_bursty_client() {
+        async fn slow(ws: WsRequest<EndUserPrincipal>) -> Response {
+            ws.on_session(|mut session: WsSession<EndUserPrincipal, Ping, Pong>| async move {
+                while let Some(ping) = session.recv().await {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    session.send(&Pong { seq: ping.seq }).await;
+                }
+            })
+        }
+
+        let hub = WsHub::new();
+        hub.configure(WsConfig { send_buffer: 2, ..Default::default() });
+        let router = Router::new().route("/ws", get(slow)).layer(Extension(hub.clone()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        let principal = r#"{"id":7,"account":"alice","tenant_id":"t1"}"#;
+        let mut client = connect(addr, Some(principal)).await.unwrap();
+        for seq in 0..20 {
+            client.send(ClientMessage::text(format!(r#"{{"seq":{}}}"#, seq))).await.unwrap();
+        }
+
+        // 驱动不会阻塞在接收队列上，连接以 1013 关闭而不是挂起
+        let close = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match client.next().await.unwrap().unwrap() {
+                    ClientMessage::Close(Some(frame)) => break u16::from(frame.code),
+                    ClientMessage::Text(_) | ClientMessage::Ping(_) | ClientMessage::Pong(_) => continue,
+                    other => panic!("unexpected message: {:?}", other),
+                }
+            }
+        });
+        assert_eq!(close.await.unwrap(), CLOSE_TRY_AGAIN_LATER);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(hub.is_empty());
+    }
+}
@@ -4,6 +4,8 @@ pub mod component;
 pub mod signal;
 pub mod version;
 pub mod component_factory;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 
 pub use {anyhow, async_trait, config, vergen_pretty, clap};
 
@@ -0,0 +1,29 @@
+use std::sync::LazyLock;
+
+pub use prometheus;
+use prometheus::core::Collector;
+use prometheus::{Encoder, Registry, TextEncoder};
+use tracing::warn;
+
+static GLOBAL_REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+/// 全局指标注册表，各组件的指标统一注册到这里，由 `/metrics` 端点导出
+pub fn registry() -> &'static Registry {
+    &GLOBAL_REGISTRY
+}
+
+/// 注册指标，重复注册时仅打印警告，便于组件被多次构建
+pub fn register<C: Collector + 'static>(collector: C) {
+    if let Err(err) = GLOBAL_REGISTRY.register(Box::new(collector)) {
+        warn!(?err, "register metrics collector failed");
+    }
+}
+
+/// 以 Prometheus 文本格式导出全局注册表中的全部指标
+pub fn gather_text() -> String {
+    let mut buf = Vec::new();
+    if let Err(err) = TextEncoder::new().encode(&GLOBAL_REGISTRY.gather(), &mut buf) {
+        warn!(?err, "encode metrics failed");
+    }
+    String::from_utf8(buf).unwrap_or_default()
+}
@@ -66,3 +66,26 @@ pub fn derive_paginated_filter(input: TokenStream) -> TokenStream {
 
     expanded.into()
 }
+
+/// 声明处理函数的中间件，由 `baizekit_api::build::Builder` 生成路由时读取，例如 `#[middleware(auth, audit)]`
+///
+/// 中间件为 `axum::middleware::from_fn_with_state` 可接受的函数，先声明的在最外层。
+#[proc_macro_attribute]
+pub fn middleware(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let parser = syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated;
+    if let Err(err) = syn::parse::Parser::parse(parser, attr) {
+        return err.to_compile_error().into();
+    }
+    item
+}
+
+/// 声明处理函数所属的路由分组，由 `baizekit_api::build::Builder` 生成路由时读取，例如 `#[api_group(v1)]`
+///
+/// 可声明在处理函数、`impl` 块或内联的 `mod` 上。
+#[proc_macro_attribute]
+pub fn api_group(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if let Err(err) = syn::parse::<syn::Ident>(attr) {
+        return err.to_compile_error().into();
+    }
+    item
+}
@@ -1,4 +1,6 @@
 pub mod config;
+#[cfg(feature = "metrics")]
+mod metrics;
 pub mod sync_producer;
 
 pub use rdkafka;
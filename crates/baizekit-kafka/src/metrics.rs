@@ -0,0 +1,40 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use baizekit_app::metrics::prometheus::{HistogramVec, IntCounterVec, histogram_opts, opts};
+use baizekit_app::metrics::register;
+
+/// Kafka 生产者指标，首次访问时注册到全局指标注册表
+pub(crate) struct ProducerMetrics {
+    messages: IntCounterVec,
+    duration: HistogramVec,
+}
+
+static PRODUCER_METRICS: LazyLock<ProducerMetrics> = LazyLock::new(|| {
+    let messages = IntCounterVec::new(
+        opts!("kafka_producer_messages_total", "Total number of messages sent by kafka producer"),
+        &["topic", "status"],
+    )
+    .expect("invalid kafka_producer_messages_total metric");
+    let duration = HistogramVec::new(
+        histogram_opts!("kafka_producer_send_duration_seconds", "Kafka send and flush latency in seconds"),
+        &["topic"],
+    )
+    .expect("invalid kafka_producer_send_duration_seconds metric");
+
+    register(messages.clone());
+    register(duration.clone());
+    ProducerMetrics { messages, duration }
+});
+
+impl ProducerMetrics {
+    pub(crate) fn global() -> &'static ProducerMetrics {
+        &PRODUCER_METRICS
+    }
+
+    pub(crate) fn observe(&self, topic: &str, success: bool, elapsed: Duration) {
+        let status = if success { "success" } else { "error" };
+        self.messages.with_label_values(&[topic, status]).inc();
+        self.duration.with_label_values(&[topic]).observe(elapsed.as_secs_f64());
+    }
+}
@@ -43,7 +43,14 @@ impl ProducerActor {
         for cmd in self.receiver.iter() {
             match cmd {
                 Command::SendWithFlush { message, respond_to, flush_timeout } => {
+                    #[cfg(feature = "metrics")]
+                    let (topic, start) = (message.topic.clone(), std::time::Instant::now());
+
                     let result = self.send_with_flush(message, flush_timeout.unwrap_or(DEFAULT_FLUSH_TIMEOUT));
+
+                    #[cfg(feature = "metrics")]
+                    crate::metrics::ProducerMetrics::global().observe(&topic, result.is_ok(), start.elapsed());
+
                     if let Err(err) = respond_to.send(result) {
                         tracing::error!(?err, "send respond_to msg failed")
                     }
@@ -1,15 +1,26 @@
-use crate::config::LogConfig;
-use crate::format::LogFormat;
-use crate::timer::LocalTimer;
+use std::sync::Arc;
+
 use baizekit_app::application::ApplicationInner;
 use baizekit_app::async_trait::async_trait;
 use baizekit_app::component::Component;
-use std::sync::Arc;
+use tracing::Subscriber;
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::LogConfig;
+use crate::format::LogFormat;
+use crate::timer::LocalTimer;
 
 pub struct LogComponent {
     #[allow(unused)]
     guard: WorkerGuard,
+    #[cfg(feature = "otel")]
+    tracer_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
 }
 
 impl LogComponent {
@@ -18,25 +29,132 @@ impl LogComponent {
         let conf: LogConfig = conf.get("log")?;
 
         let (non_blocking, guard) = tracing_appender::non_blocking(std::io::stdout());
+        let registry = subscriber(&conf, non_blocking);
 
-        // 初始化并设置日志格式(定制和筛选日志)
-        let sub_builder = tracing_subscriber::fmt()
-            .with_ansi(conf.ansi)
-            .with_file(conf.with_filename)
-            .with_line_number(conf.with_line_number)
-            .with_max_level(conf.level)
-            .with_timer(LocalTimer)
-            .with_writer(non_blocking);
-
-        match conf.format {
-            LogFormat::Compact => sub_builder.compact().init(),
-            LogFormat::Pretty => sub_builder.pretty().init(),
-            LogFormat::Json => sub_builder.json().init(),
-        };
-
-        Ok(LogComponent { guard })
+        // 配置了 otel 时额外导出链路
+        #[cfg(feature = "otel")]
+        {
+            let tracer_provider = conf.otel.as_ref().map(crate::otel::init_tracer_provider).transpose()?;
+            registry.with(tracer_provider.as_ref().map(crate::otel::layer)).init();
+            Ok(LogComponent { guard, tracer_provider })
+        }
+
+        #[cfg(not(feature = "otel"))]
+        {
+            if conf.otel.is_some() {
+                baizekit_app::anyhow::bail!("log.otel 已配置，但未启用 baizekit-log 的 otel feature");
+            }
+            registry.init();
+            Ok(LogComponent { guard })
+        }
     }
 }
 
+/// 初始化并设置日志格式(定制和筛选日志)，等级过滤对之后添加的链路导出同样生效
+fn subscriber<W>(conf: &LogConfig, writer: W) -> impl Subscriber + for<'span> LookupSpan<'span> + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_ansi(conf.ansi)
+        .with_file(conf.with_filename)
+        .with_line_number(conf.with_line_number)
+        .with_timer(LocalTimer)
+        .with_writer(writer);
+
+    let fmt_layer = match conf.format {
+        LogFormat::Compact => fmt_layer.compact().boxed(),
+        LogFormat::Pretty => fmt_layer.pretty().boxed(),
+        LogFormat::Json => fmt_layer.json().boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::from_level(conf.level))
+        .with(fmt_layer)
+}
+
 #[async_trait]
-impl Component for LogComponent {}
+impl Component for LogComponent {
+    async fn shutdown(&self) -> baizekit_app::anyhow::Result<()> {
+        // 退出前导出剩余的链路数据
+        #[cfg(feature = "otel")]
+        if let Some(provider) = &self.tracer_provider {
+            provider.shutdown()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    use tracing::Level;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'w> MakeWriter<'w> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'w self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl Buffer {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            let output = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            output.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        }
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let conf = LogConfig { format: LogFormat::Json, level: Level::WARN, ..Default::default() };
+        let buffer = Buffer::default();
+
+        tracing::subscriber::with_default(subscriber(&conf, buffer.clone()), || {
+            tracing::info!("hidden");
+            tracing::warn!("shown");
+        });
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["fields"]["message"], "shown");
+        assert_eq!(lines[0]["level"], "WARN");
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_level_filter_applies_to_otel() {
+        use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+
+        let conf = LogConfig { level: Level::WARN, ..Default::default() };
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let subscriber = subscriber(&conf, std::io::sink).with(crate::otel::layer(&provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("hidden").in_scope(|| {});
+            tracing::warn_span!("shown").in_scope(|| {});
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let names: Vec<_> = spans.iter().map(|span| span.name.as_ref()).collect();
+        assert_eq!(names, vec!["shown"]);
+    }
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::DisplayFromStr;
 pub use tracing::Level;
 
@@ -20,6 +20,58 @@ pub struct LogConfig {
     pub with_line_number: bool,
     /// 是否显示时间
     pub with_time: bool,
+    /// OpenTelemetry 链路导出配置，需启用 `otel` feature
+    pub otel: Option<OtelConfig>,
+}
+
+/// OpenTelemetry 链路导出配置，读取自 `log.otel`
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OtelConfig {
+    /// OTLP gRPC 端点
+    pub endpoint: String,
+    /// 服务名称，即 `service.name` 资源属性
+    pub service_name: String,
+    /// 采样比例，取值 0.0 ~ 1.0；上游已采样的请求始终采样
+    pub sample_ratio: SampleRatio,
+    /// 导出超时时间(秒)
+    pub timeout_seconds: u64,
+}
+
+/// 采样比例，按位比较以保持配置类型的 `Eq`
+///
+/// 反序列化时截断到 0.0 ~ 1.0，拒绝 NaN。
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(transparent)]
+pub struct SampleRatio(pub f64);
+
+impl<'de> Deserialize<'de> for SampleRatio {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ratio = f64::deserialize(deserializer)?;
+        if ratio.is_nan() {
+            return Err(serde::de::Error::custom("sample_ratio must be a number between 0.0 and 1.0"));
+        }
+        Ok(SampleRatio(ratio.clamp(0.0, 1.0)))
+    }
+}
+
+impl PartialEq for SampleRatio {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for SampleRatio {}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        OtelConfig {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "unknown_service".to_string(),
+            sample_ratio: SampleRatio(1.0),
+            timeout_seconds: 10,
+        }
+    }
 }
 
 impl Default for LogConfig {
@@ -31,6 +83,39 @@ impl Default for LogConfig {
             with_line_number: true,
             ansi: true,
             with_time: true,
+            otel: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{Error, F64Deserializer};
+
+    use super::*;
+
+    #[test]
+    fn test_sample_ratio_clamp() {
+        let ratio = |value: f64| SampleRatio::deserialize(F64Deserializer::<Error>::new(value));
+
+        assert_eq!(ratio(0.25).unwrap(), SampleRatio(0.25));
+        assert_eq!(ratio(1.5).unwrap(), SampleRatio(1.0));
+        assert_eq!(ratio(-0.1).unwrap(), SampleRatio(0.0));
+        assert!(ratio(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_otel_config_deserialize() {
+        let conf: LogConfig = serde_json::from_value(serde_json::json!({
+            "level": "debug",
+            "otel": { "endpoint": "http://collector:4317", "sample_ratio": 2 }
+        }))
+        .unwrap();
+
+        assert_eq!(conf.level, Level::DEBUG);
+        let otel = conf.otel.unwrap();
+        assert_eq!(otel.endpoint, "http://collector:4317");
+        assert_eq!(otel.sample_ratio, SampleRatio(1.0));
+        assert_eq!(otel.service_name, "unknown_service");
+    }
+}
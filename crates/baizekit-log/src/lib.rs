@@ -1,4 +1,6 @@
 pub mod component;
 pub mod config;
 pub mod format;
+#[cfg(feature = "otel")]
+pub mod otel;
 mod timer;
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use baizekit_app::anyhow::Result;
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use tracing::Subscriber;
+use tracing_subscriber::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::OtelConfig;
+
+/// 根据配置创建 OTLP 链路导出器，并注册为全局 TracerProvider 和 W3C TraceContext 传播器
+pub fn init_tracer_provider(conf: &OtelConfig) -> Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(&conf.endpoint)
+        .with_timeout(Duration::from_secs(conf.timeout_seconds))
+        .build()?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler(conf))
+        .with_resource(Resource::builder().with_service_name(conf.service_name.clone()).build())
+        .build();
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(provider.clone());
+    Ok(provider)
+}
+
+/// 按比例采样根 span，子 span 跟随上游的采样决定
+fn sampler(conf: &OtelConfig) -> Sampler {
+    Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(conf.sample_ratio.0)))
+}
+
+/// 将 tracing span 导出到 OpenTelemetry 的 layer
+pub fn layer<S>(provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer("baizekit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::Context;
+    use opentelemetry::trace::{
+        SamplingDecision, SpanContext, SpanId, SpanKind, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry_sdk::trace::ShouldSample;
+
+    use super::*;
+    use crate::config::SampleRatio;
+
+    fn decision(ratio: f64, parent: Option<&Context>) -> SamplingDecision {
+        let conf = OtelConfig { sample_ratio: SampleRatio(ratio), ..Default::default() };
+        sampler(&conf)
+            .should_sample(parent, TraceId::from(42u128), "test", &SpanKind::Server, &[], &[])
+            .decision
+    }
+
+    #[test]
+    fn test_sampler() {
+        assert_eq!(decision(1.0, None), SamplingDecision::RecordAndSample);
+        assert_eq!(decision(0.0, None), SamplingDecision::Drop);
+
+        // 上游已采样的请求不受采样比例影响
+        let parent =
+            SpanContext::new(TraceId::from(42u128), SpanId::from(7u64), TraceFlags::SAMPLED, true, TraceState::NONE);
+        let parent = Context::new().with_remote_span_context(parent);
+        assert_eq!(decision(0.0, Some(&parent)), SamplingDecision::RecordAndSample);
+    }
+}
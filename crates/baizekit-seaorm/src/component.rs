@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::connection;
 use baizekit_app::application::ApplicationInner;
 use baizekit_app::async_trait::async_trait;
 use baizekit_app::component::Component;
 use sea_orm::{Database, DatabaseConnection};
 use tracing::info;
 
+use crate::connection;
+
 pub struct DbComponent {
     pub db: Arc<DatabaseConnection>,
     pub connections: HashMap<String, Arc<DatabaseConnection>>,
@@ -19,7 +20,10 @@ impl DbComponent {
         let db_conf: connection::Config = conf.get("db")?;
         info!(dsn_url = db_conf.url, search_path = ?db_conf.schema, "连接数据库");
         let db = Database::connect(db_conf).await.map(Arc::new)?;
-        Ok(DbComponent { db, connections: Default::default() })
+        let component = DbComponent { db, connections: Default::default() };
+        #[cfg(feature = "metrics")]
+        component.register_metrics();
+        Ok(component)
     }
 
     pub async fn new_multi_connections(
@@ -40,7 +44,18 @@ impl DbComponent {
             connections.insert(label, db);
         }
 
-        Ok(DbComponent { db, connections })
+        let component = DbComponent { db, connections };
+        #[cfg(feature = "metrics")]
+        component.register_metrics();
+        Ok(component)
+    }
+
+    /// 将默认连接和各带 label 连接的连接池状态注册到全局指标注册表
+    #[cfg(feature = "metrics")]
+    fn register_metrics(&self) {
+        let mut pools = vec![("default".to_string(), self.db.clone())];
+        pools.extend(self.connections.iter().map(|(label, db)| (label.clone(), db.clone())));
+        baizekit_app::metrics::register(crate::metrics::DbPoolCollector::new(pools));
     }
 
     pub fn get_default_connection(&self) -> Arc<DatabaseConnection> {
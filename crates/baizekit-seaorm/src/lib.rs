@@ -4,6 +4,8 @@ pub mod component;
 pub mod connection;
 pub mod curd;
 
+#[cfg(feature = "metrics")]
+pub mod metrics;
 #[cfg(feature = "migration")]
 pub mod migration;
 
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use baizekit_app::metrics::prometheus::core::{Collector, Desc};
+use baizekit_app::metrics::prometheus::proto::MetricFamily;
+use baizekit_app::metrics::prometheus::{IntGaugeVec, opts};
+use sea_orm::DatabaseConnection;
+
+/// 数据库连接池指标，在每次采集时读取连接池状态
+pub struct DbPoolCollector {
+    pools: Vec<(String, Arc<DatabaseConnection>)>,
+    connections: IntGaugeVec,
+    max_connections: IntGaugeVec,
+}
+
+impl DbPoolCollector {
+    /// pools: (连接名称, 数据库连接)，连接名称作为 `connection` 标签
+    pub fn new(pools: Vec<(String, Arc<DatabaseConnection>)>) -> Self {
+        let connections = IntGaugeVec::new(
+            opts!("db_pool_connections", "Number of database pool connections by state"),
+            &["connection", "state"],
+        )
+        .expect("invalid db_pool_connections metric");
+        let max_connections = IntGaugeVec::new(
+            opts!("db_pool_max_connections", "Maximum number of database pool connections"),
+            &["connection"],
+        )
+        .expect("invalid db_pool_max_connections metric");
+
+        Self { pools, connections, max_connections }
+    }
+}
+
+impl Collector for DbPoolCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.connections.desc().into_iter().chain(self.max_connections.desc()).collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        for (name, db) in &self.pools {
+            // 仅统计 Postgres 连接池，断开或 mock 连接没有连接池状态
+            let DatabaseConnection::SqlxPostgresPoolConnection(_) = db.as_ref() else {
+                continue;
+            };
+
+            let pool = db.get_postgres_connection_pool();
+            let idle = pool.num_idle() as i64;
+            self.connections.with_label_values(&[name, "idle"]).set(idle);
+            self.connections
+                .with_label_values(&[name, "in_use"])
+                .set(pool.size() as i64 - idle);
+            self.max_connections
+                .with_label_values(&[name])
+                .set(pool.options().get_max_connections() as i64);
+        }
+
+        self.connections
+            .collect()
+            .into_iter()
+            .chain(self.max_connections.collect())
+            .collect()
+    }
+}
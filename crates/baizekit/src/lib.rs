@@ -3,8 +3,13 @@ pub use {sea_orm, tracing};
 pub mod api {
     #[cfg(feature = "http-build")]
     pub use baizekit_api::build::*;
+    #[cfg(feature = "client")]
+    pub use baizekit_api::client::*;
     pub use baizekit_api::extract::*;
+    pub use baizekit_api::layer::*;
     pub use baizekit_api::response::*;
+    #[cfg(feature = "ws")]
+    pub use baizekit_api::ws::*;
 }
 
 pub mod app {
@@ -12,7 +17,7 @@ pub mod app {
 }
 
 pub mod log {
-    pub use baizekit_log::config::LogConfig;
+    pub use baizekit_log::config::{LogConfig, OtelConfig, SampleRatio};
     pub use baizekit_log::format::LogFormat;
 }
 